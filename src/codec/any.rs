@@ -0,0 +1,216 @@
+use crate::{codec::*, util::PartialBuffer, Algorithm, Level};
+use std::{
+    io::Result,
+    task::{Context, Poll},
+};
+
+macro_rules! dispatch {
+    (@arms $self:expr, $binding:tt => $call:expr) => {
+        match *$self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli $binding => $call,
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2 $binding => $call,
+            #[cfg(feature = "deflate")]
+            Self::Deflate $binding => $call,
+            #[cfg(feature = "gzip")]
+            Self::Gzip $binding => $call,
+            #[cfg(feature = "lz4")]
+            Self::Lz4 $binding => $call,
+            #[cfg(feature = "lzip")]
+            Self::Lzip $binding => $call,
+            #[cfg(feature = "lzma")]
+            Self::Lzma $binding => $call,
+            #[cfg(feature = "lzma2")]
+            Self::Lzma2 $binding => $call,
+            #[cfg(feature = "xz")]
+            Self::Xz $binding => $call,
+            #[cfg(feature = "zlib")]
+            Self::Zlib $binding => $call,
+            #[cfg(feature = "zstd")]
+            Self::Zstd $binding => $call,
+        }
+    };
+    ($self:expr, ref $codec:ident => $call:expr) => {
+        dispatch!(@arms $self, (ref $codec) => $call)
+    };
+    ($self:expr, $codec:ident => $call:expr) => {
+        dispatch!(@arms $self, (ref mut $codec) => $call)
+    };
+}
+
+/// Any of the encoders enabled in this build, chosen at runtime.
+#[derive(Debug)]
+pub(crate) enum AnyEncoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<BrotliEncoder>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(BzEncoder),
+    #[cfg(feature = "deflate")]
+    Deflate(DeflateEncoder),
+    #[cfg(feature = "gzip")]
+    Gzip(GzipEncoder),
+    #[cfg(feature = "lz4")]
+    Lz4(Lz4Encoder),
+    #[cfg(feature = "lzip")]
+    Lzip(LzipEncoder),
+    #[cfg(feature = "lzma")]
+    Lzma(LzmaEncoder),
+    #[cfg(feature = "lzma2")]
+    Lzma2(Lzma2Encoder),
+    #[cfg(feature = "xz")]
+    Xz(XzEncoder),
+    #[cfg(feature = "zlib")]
+    Zlib(ZlibEncoder),
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdEncoder),
+}
+
+/// Any of the decoders enabled in this build, chosen at runtime.
+#[derive(Debug)]
+pub(crate) enum AnyDecoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<BrotliDecoder>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(BzDecoder),
+    #[cfg(feature = "deflate")]
+    Deflate(DeflateDecoder),
+    #[cfg(feature = "gzip")]
+    Gzip(GzipDecoder),
+    #[cfg(feature = "lz4")]
+    Lz4(Lz4Decoder),
+    #[cfg(feature = "lzip")]
+    Lzip(LzipDecoder),
+    #[cfg(feature = "lzma")]
+    Lzma(LzmaDecoder),
+    #[cfg(feature = "lzma2")]
+    Lzma2(Lzma2Decoder),
+    #[cfg(feature = "xz")]
+    Xz(XzDecoder),
+    #[cfg(feature = "zlib")]
+    Zlib(ZlibDecoder),
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdDecoder),
+}
+
+impl AnyEncoder {
+    pub(crate) fn new(algorithm: Algorithm, level: Level) -> Self {
+        match algorithm {
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => Self::Brotli(Box::new(BrotliEncoder::new(
+                level.into_brotli(Default::default()),
+            ))),
+            #[cfg(feature = "bzip2")]
+            Algorithm::Bzip2 => Self::Bzip2(BzEncoder::new(level.into_bzip2(), 0)),
+            #[cfg(feature = "deflate")]
+            Algorithm::Deflate => Self::Deflate(DeflateEncoder::new(level.into_flate2())),
+            #[cfg(feature = "gzip")]
+            Algorithm::Gzip => Self::Gzip(GzipEncoder::new(level.into_flate2())),
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => Self::Lz4(Lz4Encoder::new(
+                crate::Lz4Params::default().into_preferences(level.into_lz4()),
+            )),
+            #[cfg(feature = "lzip")]
+            Algorithm::Lzip => Self::Lzip(LzipEncoder::new(level.into_xz2())),
+            #[cfg(feature = "lzma")]
+            Algorithm::Lzma => Self::Lzma(LzmaEncoder::new(level.into_xz2())),
+            #[cfg(feature = "lzma2")]
+            Algorithm::Lzma2 => Self::Lzma2(Lzma2Encoder::new(level.into_xz2())),
+            #[cfg(feature = "xz")]
+            Algorithm::Xz => Self::Xz(XzEncoder::new(level.into_xz2())),
+            #[cfg(feature = "zlib")]
+            Algorithm::Zlib => Self::Zlib(ZlibEncoder::new(level.into_flate2())),
+            #[cfg(feature = "zstd")]
+            Algorithm::Zstd => Self::Zstd(ZstdEncoder::new(level.into_zstd())),
+        }
+    }
+}
+
+impl AnyDecoder {
+    pub(crate) fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => Self::Brotli(Box::new(BrotliDecoder::new())),
+            #[cfg(feature = "bzip2")]
+            Algorithm::Bzip2 => Self::Bzip2(BzDecoder::new()),
+            #[cfg(feature = "deflate")]
+            Algorithm::Deflate => Self::Deflate(DeflateDecoder::new()),
+            #[cfg(feature = "gzip")]
+            Algorithm::Gzip => Self::Gzip(GzipDecoder::new()),
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => Self::Lz4(Lz4Decoder::new()),
+            #[cfg(feature = "lzip")]
+            Algorithm::Lzip => Self::Lzip(LzipDecoder::new()),
+            #[cfg(feature = "lzma")]
+            Algorithm::Lzma => Self::Lzma(LzmaDecoder::new()),
+            #[cfg(feature = "lzma2")]
+            Algorithm::Lzma2 => Self::Lzma2(Lzma2Decoder::new()),
+            #[cfg(feature = "xz")]
+            Algorithm::Xz => Self::Xz(XzDecoder::new()),
+            #[cfg(feature = "zlib")]
+            Algorithm::Zlib => Self::Zlib(ZlibDecoder::new()),
+            #[cfg(feature = "zstd")]
+            Algorithm::Zstd => Self::Zstd(ZstdDecoder::new()),
+        }
+    }
+}
+
+impl Encode for AnyEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        dispatch!(self, codec => codec.encode(input, output))
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        dispatch!(self, codec => codec.flush(output))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        dispatch!(self, codec => codec.finish(output))
+    }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        dispatch!(self, codec => codec.poll_wait(cx))
+    }
+}
+
+impl Decode for AnyDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        dispatch!(self, codec => codec.reinit())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        dispatch!(self, codec => codec.decode(input, output))
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        dispatch!(self, codec => codec.flush(output))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        dispatch!(self, codec => codec.finish(output))
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        dispatch!(self, ref codec => codec.is_member_start(input))
+    }
+}
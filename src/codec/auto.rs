@@ -0,0 +1,221 @@
+use crate::{
+    codec::{AnyDecoder, Decode},
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::io::{Error, ErrorKind, Result};
+
+/// The longest signature that may need to be read to detect the format.
+const HEADER_LEN: usize = 6;
+
+/// Formats identified by a fixed signature at the start of the stream.
+const SIGNATURES: &[(Algorithm, &[u8])] = &[
+    #[cfg(feature = "bzip2")]
+    (Algorithm::Bzip2, b"BZh"),
+    #[cfg(feature = "gzip")]
+    (Algorithm::Gzip, &[0x1f, 0x8b]),
+    #[cfg(feature = "lz4")]
+    (Algorithm::Lz4, &[0x04, 0x22, 0x4d, 0x18]),
+    #[cfg(feature = "lzip")]
+    (Algorithm::Lzip, b"LZIP"),
+    // The properties byte written by every preset, followed by the two low bytes of the
+    // little-endian dictionary size, which are zero for the multiples of 64 KiB the presets use.
+    #[cfg(feature = "lzma")]
+    (Algorithm::Lzma, &[0x5d, 0x00, 0x00]),
+    #[cfg(feature = "xz")]
+    (Algorithm::Xz, &[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00]),
+    #[cfg(feature = "zstd")]
+    (Algorithm::Zstd, &[0x28, 0xb5, 0x2f, 0xfd]),
+];
+
+#[derive(Debug)]
+enum Detection {
+    Found(Algorithm),
+    Incomplete,
+    Unknown,
+}
+
+fn detect(header: &[u8]) -> Detection {
+    let mut incomplete = false;
+
+    for &(algorithm, signature) in SIGNATURES {
+        if header.starts_with(signature) {
+            return Detection::Found(algorithm);
+        }
+        incomplete |= signature.starts_with(header);
+    }
+
+    #[cfg(feature = "zlib")]
+    match crate::codec::zlib::match_header(header) {
+        Some(true) => return Detection::Found(Algorithm::Zlib),
+        Some(false) => {}
+        None => incomplete = true,
+    }
+
+    if incomplete {
+        Detection::Incomplete
+    } else {
+        Detection::Unknown
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Detecting,
+    Decoding(Box<AnyDecoder>),
+    Passthrough,
+}
+
+#[derive(Debug)]
+pub struct AutoDecoder {
+    state: State,
+    algorithm: Option<Algorithm>,
+    passthrough: bool,
+    header: PartialBuffer<[u8; HEADER_LEN]>,
+    replayed: usize,
+}
+
+impl AutoDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: State::Detecting,
+            algorithm: None,
+            passthrough: false,
+            header: PartialBuffer::new([0; HEADER_LEN]),
+            replayed: 0,
+        }
+    }
+
+    pub(crate) fn passthrough(&mut self, enabled: bool) {
+        self.passthrough = enabled;
+    }
+
+    pub(crate) fn algorithm(&self) -> Option<Algorithm> {
+        self.algorithm
+    }
+
+    /// Picks the format from the bytes read so far, `at_end` signals no more input is available.
+    fn select(&mut self, at_end: bool) -> Result<()> {
+        match detect(self.header.written()) {
+            Detection::Found(algorithm) => {
+                self.state = State::Decoding(Box::new(AnyDecoder::new(algorithm)));
+                self.algorithm = Some(algorithm);
+            }
+            Detection::Incomplete if !at_end && !self.header.unwritten().is_empty() => {}
+            _ if self.passthrough => self.state = State::Passthrough,
+            Detection::Incomplete => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "unexpected end of input while detecting compression format",
+                ));
+            }
+            Detection::Unknown => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "unrecognized compression format",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Feeds the bytes read during detection through to the selected format, returns whether they
+    /// have all been consumed.
+    fn replay(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let mut header = PartialBuffer::new(&self.header.written()[self.replayed..]);
+        match &mut self.state {
+            _ if header.unwritten().is_empty() => return Ok(true),
+            State::Detecting => return Ok(false),
+            State::Decoding(decoder) => {
+                decoder.decode(&mut header, output)?;
+            }
+            State::Passthrough => output.copy_unwritten_from(&mut header),
+        }
+        self.replayed += header.written().len();
+        Ok(header.unwritten().is_empty())
+    }
+}
+
+impl Decode for AutoDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        match &mut self.state {
+            State::Decoding(decoder) => decoder.reinit(),
+            State::Detecting | State::Passthrough => Ok(()),
+        }
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if let State::Detecting = self.state {
+            self.header.copy_unwritten_from(input);
+            self.select(false)?;
+        }
+
+        if !self.replay(output)? {
+            return Ok(false);
+        }
+
+        match &mut self.state {
+            // The rest of the input may have been taken into the header, some decoders fail if
+            // called without any input.
+            _ if input.unwritten().is_empty() => Ok(false),
+            State::Detecting => Ok(false),
+            State::Decoding(decoder) => decoder.decode(input, output),
+            State::Passthrough => {
+                output.copy_unwritten_from(input);
+                Ok(false)
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if let State::Detecting = self.state {
+            return Ok(true);
+        }
+
+        if !self.replay(output)? {
+            return Ok(false);
+        }
+
+        match &mut self.state {
+            State::Detecting => unreachable!(),
+            State::Decoding(decoder) => decoder.flush(output),
+            State::Passthrough => Ok(true),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if let State::Detecting = self.state {
+            self.select(true)?;
+        }
+
+        if !self.replay(output)? {
+            return Ok(false);
+        }
+
+        match &mut self.state {
+            State::Detecting => unreachable!(),
+            State::Decoding(decoder) => decoder.finish(output),
+            State::Passthrough => Ok(true),
+        }
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        match &self.state {
+            State::Decoding(decoder) => decoder.is_member_start(input),
+            State::Detecting | State::Passthrough => Some(true),
+        }
+    }
+}
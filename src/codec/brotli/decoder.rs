@@ -8,19 +8,33 @@ use brotli::{enc::StandardAlloc, BrotliDecompressStream, BrotliResult, BrotliSta
 
 pub struct BrotliDecoder {
     state: BrotliState<StandardAlloc, StandardAlloc, StandardAlloc>,
+    large_window: bool,
+}
+
+fn new_state(large_window: bool) -> BrotliState<StandardAlloc, StandardAlloc, StandardAlloc> {
+    let mut state = BrotliState::new(
+        StandardAlloc::default(),
+        StandardAlloc::default(),
+        StandardAlloc::default(),
+    );
+    state.large_window = large_window;
+    state
 }
 
 impl BrotliDecoder {
     pub(crate) fn new() -> Self {
         Self {
-            state: BrotliState::new(
-                StandardAlloc::default(),
-                StandardAlloc::default(),
-                StandardAlloc::default(),
-            ),
+            state: new_state(false),
+            large_window: false,
         }
     }
 
+    /// Must be called before decoding starts, as the window size is read from the stream header.
+    pub(crate) fn large_window(&mut self, enabled: bool) {
+        self.large_window = enabled;
+        self.state.large_window = enabled;
+    }
+
     fn decode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -57,11 +71,7 @@ impl BrotliDecoder {
 
 impl Decode for BrotliDecoder {
     fn reinit(&mut self) -> Result<()> {
-        self.state = BrotliState::new(
-            StandardAlloc::default(),
-            StandardAlloc::default(),
-            StandardAlloc::default(),
-        );
+        self.state = new_state(self.large_window);
         Ok(())
     }
 
@@ -102,4 +102,8 @@ impl Decode for BzDecoder {
     ) -> Result<bool> {
         Ok(true)
     }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        crate::codec::match_signature(b"BZh", input)
+    }
 }
@@ -0,0 +1,224 @@
+use crate::{
+    codec::{AnyDecoder, Decode},
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::io::{Error, ErrorKind, Result};
+
+const PIPE_BUFFER_SIZE: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Decode,
+    Flush,
+    Finish,
+}
+
+#[derive(Debug)]
+struct Stage {
+    decoder: AnyDecoder,
+    done: bool,
+    flushed: bool,
+}
+
+impl Stage {
+    /// Runs the decoder once on whatever input and output space is available, returns whether
+    /// any progress was made.
+    fn step(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        upstream_done: bool,
+        mode: Mode,
+    ) -> Result<bool> {
+        let (input_len, output_len) = (input.written().len(), output.written().len());
+
+        if !input.unwritten().is_empty() {
+            self.flushed = false;
+            self.done = self.decoder.decode(input, output)?;
+        } else if upstream_done {
+            self.done = self.decoder.finish(output)?;
+        } else if mode == Mode::Flush && !self.flushed {
+            self.flushed = self.decoder.flush(output)?;
+        }
+
+        Ok(self.done || input.written().len() != input_len || output.written().len() != output_len)
+    }
+}
+
+/// Holds the output of one stage until the next stage consumes it.
+#[derive(Debug)]
+struct Pipe {
+    buffer: Box<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+impl Pipe {
+    fn new() -> Self {
+        Self {
+            buffer: vec![0; PIPE_BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    fn read<T>(&mut self, f: impl FnOnce(&mut PartialBuffer<&[u8]>) -> T) -> T {
+        let mut input = PartialBuffer::new(&self.buffer[self.start..self.end]);
+        let result = f(&mut input);
+        self.start += input.written().len();
+        if self.is_empty() {
+            self.clear();
+        }
+        result
+    }
+
+    fn write<T>(&mut self, f: impl FnOnce(&mut PartialBuffer<&mut [u8]>) -> T) -> T {
+        if self.end == self.buffer.len() && self.start > 0 {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        let mut output = PartialBuffer::new(&mut self.buffer[self.end..]);
+        let result = f(&mut output);
+        self.end += output.written().len();
+        result
+    }
+}
+
+/// Decodes data which had multiple encodings applied one after the other, such as from an HTTP
+/// `Content-Encoding` header.
+#[derive(Debug)]
+pub struct ChainDecoder {
+    /// In the order they must be decoded, the reverse of the order the encodings were applied.
+    stages: Vec<Stage>,
+    /// Between each pair of consecutive stages.
+    pipes: Vec<Pipe>,
+}
+
+impl ChainDecoder {
+    /// `encodings` are in the order they were applied to the data.
+    pub(crate) fn new(encodings: &[Algorithm]) -> Self {
+        Self {
+            stages: encodings
+                .iter()
+                .rev()
+                .map(|&algorithm| Stage {
+                    decoder: AnyDecoder::new(algorithm),
+                    done: false,
+                    flushed: false,
+                })
+                .collect(),
+            pipes: (1..encodings.len()).map(|_| Pipe::new()).collect(),
+        }
+    }
+
+    /// Runs the stages until no more progress can be made, returns whether the last stage is
+    /// done, or when flushing whether all stages are flushed.
+    fn run(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        mode: Mode,
+    ) -> Result<bool> {
+        let last = match self.stages.len() {
+            0 => {
+                output.copy_unwritten_from(input);
+                return Ok(mode != Mode::Decode);
+            }
+            len => len - 1,
+        };
+
+        loop {
+            let mut progress = false;
+
+            for i in 0..=last {
+                let upstream_done = match i {
+                    0 => mode == Mode::Finish,
+                    _ => self.stages[i - 1].done,
+                };
+                let (before, after) = self.pipes.split_at_mut(i);
+                let stage = &mut self.stages[i];
+
+                if stage.done {
+                    if i > 0 && !before[i - 1].is_empty() {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "trailing data after the end of an encoding",
+                        ));
+                    }
+                    continue;
+                }
+
+                progress |= match (i == 0, i == last) {
+                    (true, true) => stage.step(input, output, upstream_done, mode)?,
+                    (true, false) => {
+                        after[0].write(|output| stage.step(input, output, upstream_done, mode))?
+                    }
+                    (false, true) => before[i - 1]
+                        .read(|input| stage.step(input, output, upstream_done, mode))?,
+                    (false, false) => before[i - 1].read(|input| {
+                        after[0].write(|output| stage.step(input, output, upstream_done, mode))
+                    })?,
+                };
+            }
+
+            if !progress || self.stages[last].done {
+                break;
+            }
+        }
+
+        Ok(match mode {
+            Mode::Decode | Mode::Finish => self.stages[last].done,
+            Mode::Flush => {
+                self.stages.iter().all(|stage| stage.done || stage.flushed)
+                    && self.pipes.iter().all(Pipe::is_empty)
+            }
+        })
+    }
+}
+
+impl Decode for ChainDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        for stage in &mut self.stages {
+            stage.decoder.reinit()?;
+            stage.done = false;
+            stage.flushed = false;
+        }
+        for pipe in &mut self.pipes {
+            pipe.clear();
+        }
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.run(input, output, Mode::Decode)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.run(&mut PartialBuffer::new(&[][..]), output, Mode::Flush)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.run(&mut PartialBuffer::new(&[][..]), output, Mode::Finish)
+    }
+}
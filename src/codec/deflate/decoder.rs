@@ -4,12 +4,33 @@ use std::io::Result;
 #[derive(Debug)]
 pub struct DeflateDecoder {
     inner: crate::codec::FlateDecoder,
+    dictionary: Option<Vec<u8>>,
 }
 
 impl DeflateDecoder {
     pub(crate) fn new() -> Self {
         Self {
             inner: crate::codec::FlateDecoder::new(false),
+            dictionary: None,
+        }
+    }
+
+    pub(crate) fn with_dictionary(dictionary: &[u8]) -> Result<Self> {
+        let mut inner = crate::codec::FlateDecoder::new(false);
+        inner.set_dictionary(dictionary)?;
+        Ok(Self {
+            inner,
+            dictionary: Some(dictionary.to_vec()),
+        })
+    }
+
+    pub(crate) fn window_bits(&mut self, bits: u32) {
+        self.inner.window_bits(bits);
+        // Changing the window restarts the stream, losing the dictionary
+        if let Some(dictionary) = &self.dictionary {
+            self.inner
+                .set_dictionary(dictionary)
+                .expect("dictionary was set before");
         }
     }
 }
@@ -17,6 +38,9 @@ impl DeflateDecoder {
 impl crate::codec::Decode for DeflateDecoder {
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()?;
+        if let Some(dictionary) = &self.dictionary {
+            self.inner.set_dictionary(dictionary)?;
+        }
         Ok(())
     }
 
@@ -14,6 +14,18 @@ impl DeflateEncoder {
             inner: crate::codec::FlateEncoder::new(level, false),
         }
     }
+
+    pub(crate) fn with_dictionary(level: Compression, dictionary: &[u8]) -> Result<Self> {
+        let mut inner = crate::codec::FlateEncoder::new(level, false);
+        inner.set_dictionary(dictionary)?;
+        Ok(Self { inner })
+    }
+
+    pub(crate) fn with_params(level: Compression, params: crate::FlateParams) -> Self {
+        Self {
+            inner: crate::codec::FlateEncoder::with_params(level, false, params),
+        }
+    }
 }
 
 impl Encode for DeflateEncoder {
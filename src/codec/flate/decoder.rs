@@ -1,46 +1,50 @@
-use crate::{codec::Decode, util::PartialBuffer};
-use std::io::{Error, ErrorKind, Result};
-
-use flate2::{Decompress, FlushDecompress, Status};
+use crate::{
+    codec::{
+        flate::stream::{self, Stream},
+        Decode,
+    },
+    unshared::Unshared,
+    util::PartialBuffer,
+};
+use std::{
+    io::{Error, ErrorKind, Result},
+    os::raw::c_int,
+};
 
 #[derive(Debug)]
 pub struct FlateDecoder {
-    zlib_header: bool,
-    decompress: Decompress,
+    stream: Unshared<Stream>,
+    /// The `windowBits` given to zlib, negative for raw deflate.
+    window_bits: c_int,
 }
 
 impl FlateDecoder {
     pub(crate) fn new(zlib_header: bool) -> Self {
+        let window_bits = if zlib_header { 15 } else { -15 };
         Self {
-            zlib_header,
-            decompress: Decompress::new(zlib_header),
+            stream: Unshared::new(Stream::inflate(window_bits).expect("failed to initialize zlib")),
+            window_bits,
         }
     }
 
-    fn decode(
-        &mut self,
-        input: &mut PartialBuffer<impl AsRef<[u8]>>,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
-        flush: FlushDecompress,
-    ) -> Result<Status> {
-        let prior_in = self.decompress.total_in();
-        let prior_out = self.decompress.total_out();
-
-        let status =
-            self.decompress
-                .decompress(input.unwritten(), output.unwritten_mut(), flush)?;
-
-        input.advance((self.decompress.total_in() - prior_in) as usize);
-        output.advance((self.decompress.total_out() - prior_out) as usize);
+    /// Rejects data referencing more than the last `2^bits` bytes, from 8 to 15. This restarts
+    /// the stream, so has to be done before decoding.
+    pub(crate) fn window_bits(&mut self, bits: u32) {
+        let bits = bits.clamp(8, 15) as c_int;
+        self.window_bits = if self.window_bits < 0 { -bits } else { bits };
+        // Resetting can only fail with invalid parameters, and the window bits are clamped
+        self.reinit().expect("failed to reset zlib");
+    }
 
-        Ok(status)
+    /// Sets the preset dictionary for a raw deflate stream.
+    pub(crate) fn set_dictionary(&mut self, dictionary: &[u8]) -> Result<()> {
+        self.stream.get_mut().set_dictionary(dictionary)
     }
 }
 
 impl Decode for FlateDecoder {
     fn reinit(&mut self) -> Result<()> {
-        self.decompress.reset(self.zlib_header);
-        Ok(())
+        self.stream.get_mut().inflate_reset(self.window_bits)
     }
 
     fn decode(
@@ -48,35 +52,23 @@ impl Decode for FlateDecoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        match self.decode(input, output, FlushDecompress::None)? {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::BufError => Err(Error::new(ErrorKind::Other, "unexpected BufError")),
-        }
+        let ret = self
+            .stream
+            .get_mut()
+            .code(input, output, stream::NO_FLUSH)?;
+        Ok(ret == stream::STREAM_END)
     }
 
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.decode(
-            &mut PartialBuffer::new(&[][..]),
-            output,
-            FlushDecompress::Sync,
-        )?;
-
-        loop {
-            let old_len = output.written().len();
-            self.decode(
-                &mut PartialBuffer::new(&[][..]),
-                output,
-                FlushDecompress::None,
-            )?;
-            if output.written().len() == old_len {
-                break;
-            }
-        }
+        self.stream
+            .get_mut()
+            .code(&mut PartialBuffer::new(&[][..]), output, stream::SYNC_FLUSH)?;
 
+        // zlib writes out everything it can decode, so it's only holding data back if it ran out
+        // of space
         Ok(!output.unwritten().is_empty())
     }
 
@@ -84,14 +76,23 @@ impl Decode for FlateDecoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        match self.decode(
+        let prior = output.written().len();
+        let ret = self.stream.get_mut().code(
             &mut PartialBuffer::new(&[][..]),
             output,
-            FlushDecompress::Finish,
-        )? {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::BufError => Err(Error::new(ErrorKind::Other, "unexpected BufError")),
+            stream::NO_FLUSH,
+        )?;
+
+        if ret == stream::STREAM_END {
+            Ok(true)
+        } else if output.written().len() > prior || output.unwritten().is_empty() {
+            // Without more input zlib can still write out the rest of a match that didn't fit
+            Ok(false)
+        } else {
+            Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "deflate stream ended unexpectedly",
+            ))
         }
     }
 }
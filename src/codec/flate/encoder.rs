@@ -1,39 +1,59 @@
-use crate::{codec::Encode, util::PartialBuffer};
-use std::io::{Error, ErrorKind, Result};
+use crate::{
+    codec::{
+        flate::stream::{self, Stream},
+        Encode,
+    },
+    unshared::Unshared,
+    util::PartialBuffer,
+};
+use std::{io::Result, os::raw::c_int};
 
-use flate2::{Compress, Compression, FlushCompress, Status};
+use flate2::Compression;
 
 #[derive(Debug)]
 pub struct FlateEncoder {
-    compress: Compress,
+    stream: Unshared<Stream>,
     flushed: bool,
 }
 
 impl FlateEncoder {
     pub(crate) fn new(level: Compression, zlib_header: bool) -> Self {
-        Self {
-            compress: Compress::new(level, zlib_header),
-            flushed: true,
-        }
+        Self::with_params(level, zlib_header, crate::FlateParams::default())
     }
 
-    fn encode(
-        &mut self,
-        input: &mut PartialBuffer<impl AsRef<[u8]>>,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
-        flush: FlushCompress,
-    ) -> Result<Status> {
-        let prior_in = self.compress.total_in();
-        let prior_out = self.compress.total_out();
+    pub(crate) fn with_params(
+        level: Compression,
+        zlib_header: bool,
+        params: crate::FlateParams,
+    ) -> Self {
+        // zlib stops at level 9, `flate2`'s level 10 is specific to miniz
+        let level = level.level().min(9) as c_int;
+        let window_bits = params.window_bits_or_default() as c_int;
+        let window_bits = if zlib_header {
+            window_bits
+        } else {
+            -window_bits
+        };
 
-        let status = self
-            .compress
-            .compress(input.unwritten(), output.unwritten_mut(), flush)?;
+        // The parameters are all clamped to zlib's ranges, so this can only fail by running out
+        // of memory
+        let stream = Stream::deflate(
+            level,
+            window_bits,
+            params.mem_level_or_default(),
+            params.zlib_strategy(),
+        )
+        .expect("failed to initialize zlib");
 
-        input.advance((self.compress.total_in() - prior_in) as usize);
-        output.advance((self.compress.total_out() - prior_out) as usize);
+        Self {
+            stream: Unshared::new(stream),
+            flushed: true,
+        }
+    }
 
-        Ok(status)
+    /// Sets the preset dictionary, this must be done before any input is given.
+    pub(crate) fn set_dictionary(&mut self, dictionary: &[u8]) -> Result<()> {
+        self.stream.get_mut().set_dictionary(dictionary)
     }
 }
 
@@ -44,11 +64,10 @@ impl Encode for FlateEncoder {
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()> {
         self.flushed = false;
-        match self.encode(input, output, FlushCompress::None)? {
-            Status::Ok => Ok(()),
-            Status::StreamEnd => unreachable!(),
-            Status::BufError => Err(Error::new(ErrorKind::Other, "unexpected BufError")),
-        }
+        self.stream
+            .get_mut()
+            .code(input, output, stream::NO_FLUSH)?;
+        Ok(())
     }
 
     fn flush(
@@ -61,26 +80,18 @@ impl Encode for FlateEncoder {
             return Ok(true);
         }
 
-        self.encode(
-            &mut PartialBuffer::new(&[][..]),
-            output,
-            FlushCompress::Sync,
-        )?;
+        self.stream
+            .get_mut()
+            .code(&mut PartialBuffer::new(&[][..]), output, stream::SYNC_FLUSH)?;
 
-        loop {
-            let old_len = output.written().len();
-            self.encode(
-                &mut PartialBuffer::new(&[][..]),
-                output,
-                FlushCompress::None,
-            )?;
-            if output.written().len() == old_len {
-                break;
-            }
+        // zlib only completes the flush once it leaves space in the output, otherwise it needs
+        // calling again with the same flush
+        if output.unwritten().is_empty() {
+            return Ok(false);
         }
 
         self.flushed = true;
-        Ok(!output.unwritten().is_empty())
+        Ok(true)
     }
 
     fn finish(
@@ -88,14 +99,10 @@ impl Encode for FlateEncoder {
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
         self.flushed = false;
-        match self.encode(
-            &mut PartialBuffer::new(&[][..]),
-            output,
-            FlushCompress::Finish,
-        )? {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::BufError => Err(Error::new(ErrorKind::Other, "unexpected BufError")),
-        }
+        let ret =
+            self.stream
+                .get_mut()
+                .code(&mut PartialBuffer::new(&[][..]), output, stream::FINISH)?;
+        Ok(ret == stream::STREAM_END)
     }
 }
@@ -1,4 +1,8 @@
 mod decoder;
 mod encoder;
+mod stream;
 
 pub(crate) use self::{decoder::FlateDecoder, encoder::FlateEncoder};
+
+/// The furthest back deflate can reference, only this much of a preset dictionary is used.
+const WINDOW_SIZE: usize = 32 * 1024;
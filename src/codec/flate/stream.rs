@@ -0,0 +1,238 @@
+use crate::{codec::flate::WINDOW_SIZE, util::PartialBuffer};
+use std::{
+    alloc::{self, Layout},
+    convert::TryFrom,
+    ffi::CStr,
+    io::{Error, ErrorKind, Result},
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    ptr,
+};
+
+use libz_sys::{
+    deflate, deflateEnd, deflateInit2_, deflateSetDictionary, inflate, inflateEnd, inflateInit2_,
+    inflateReset2, inflateSetDictionary, uInt, z_stream, zlibVersion, Z_BUF_ERROR, Z_DATA_ERROR,
+    Z_DEFLATED, Z_MEM_ERROR, Z_NEED_DICT, Z_OK, Z_STREAM_END, Z_STREAM_ERROR,
+};
+
+pub(crate) use libz_sys::{
+    Z_BUF_ERROR as BUF_ERROR, Z_FINISH as FINISH, Z_NO_FLUSH as NO_FLUSH,
+    Z_STREAM_END as STREAM_END, Z_SYNC_FLUSH as SYNC_FLUSH,
+};
+
+const ALIGN: usize = mem::align_of::<usize>();
+
+/// Allocates for zlib from Rust's allocator, which needs the size again when freeing so it's
+/// stored in a header before the returned pointer.
+unsafe extern "C" fn zalloc(_opaque: *mut c_void, items: c_uint, size: c_uint) -> *mut c_void {
+    let layout = match usize::try_from(u64::from(items) * u64::from(size))
+        .ok()
+        .and_then(|size| size.checked_add(mem::size_of::<usize>()))
+        .and_then(|size| Layout::from_size_align(size, ALIGN).ok())
+    {
+        Some(layout) => layout,
+        None => return ptr::null_mut(),
+    };
+
+    let ptr = alloc::alloc(layout) as *mut usize;
+    if ptr.is_null() {
+        return ptr::null_mut();
+    }
+    *ptr = layout.size();
+    ptr.add(1) as *mut c_void
+}
+
+unsafe extern "C" fn zfree(_opaque: *mut c_void, address: *mut c_void) {
+    let ptr = (address as *mut usize).sub(1);
+    alloc::dealloc(
+        ptr as *mut u8,
+        Layout::from_size_align_unchecked(*ptr, ALIGN),
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Deflate,
+    Inflate,
+}
+
+/// An initialized zlib stream, ended when dropped.
+///
+/// This is used rather than `flate2`, whose default backend can't limit the window size, or take
+/// a memory level, strategy or preset dictionary.
+pub(crate) struct Stream {
+    // zlib's state points back at the stream, so it's boxed to keep it in place
+    stream: Box<z_stream>,
+    kind: Kind,
+}
+
+// zlib streams may be used from any thread, just not from multiple threads at once, which
+// `Unshared` takes care of.
+unsafe impl Send for Stream {}
+
+impl Stream {
+    /// Creates a compressor, writing raw deflate data for a negative `window_bits` and the zlib
+    /// format otherwise. Parameters outside of zlib's ranges are rejected with `InvalidInput`.
+    pub(crate) fn deflate(
+        level: c_int,
+        window_bits: c_int,
+        mem_level: c_int,
+        strategy: c_int,
+    ) -> Result<Self> {
+        Self::new(Kind::Deflate, |stream| unsafe {
+            deflateInit2_(
+                stream,
+                level,
+                Z_DEFLATED,
+                window_bits,
+                mem_level,
+                strategy,
+                zlibVersion(),
+                mem::size_of::<z_stream>() as c_int,
+            )
+        })
+    }
+
+    /// Creates a decompressor, reading raw deflate data for a negative `window_bits` and the
+    /// zlib format otherwise.
+    pub(crate) fn inflate(window_bits: c_int) -> Result<Self> {
+        Self::new(Kind::Inflate, |stream| unsafe {
+            inflateInit2_(
+                stream,
+                window_bits,
+                zlibVersion(),
+                mem::size_of::<z_stream>() as c_int,
+            )
+        })
+    }
+
+    fn new(kind: Kind, init: impl FnOnce(&mut z_stream) -> c_int) -> Result<Self> {
+        let mut stream = Self {
+            stream: Box::new(z_stream {
+                next_in: ptr::null_mut(),
+                avail_in: 0,
+                total_in: 0,
+                next_out: ptr::null_mut(),
+                avail_out: 0,
+                total_out: 0,
+                msg: ptr::null_mut(),
+                state: ptr::null_mut(),
+                zalloc,
+                zfree,
+                opaque: ptr::null_mut(),
+                data_type: 0,
+                adler: 0,
+                reserved: 0,
+            }),
+            kind,
+        };
+        let ret = init(&mut stream.stream);
+        stream.check(ret)?;
+        Ok(stream)
+    }
+
+    /// Resets a decompressor to start a new stream, with a new `window_bits` as for
+    /// [`inflate`](Self::inflate).
+    pub(crate) fn inflate_reset(&mut self, window_bits: c_int) -> Result<()> {
+        debug_assert_eq!(self.kind, Kind::Inflate);
+        let ret = unsafe { inflateReset2(&mut *self.stream, window_bits) };
+        self.check(ret).map(drop)
+    }
+
+    /// Sets the preset dictionary. Compressors must not have been given any input yet, raw
+    /// decompressors can have it set at any time.
+    pub(crate) fn set_dictionary(&mut self, dictionary: &[u8]) -> Result<()> {
+        // zlib only keeps the last window's worth, which also keeps the length in range
+        let dictionary = &dictionary[dictionary.len().saturating_sub(WINDOW_SIZE)..];
+        let set = match self.kind {
+            Kind::Deflate => deflateSetDictionary,
+            Kind::Inflate => inflateSetDictionary,
+        };
+        let ret = unsafe {
+            set(
+                &mut *self.stream,
+                dictionary.as_ptr(),
+                dictionary.len() as uInt,
+            )
+        };
+        self.check(ret).map(drop)
+    }
+
+    /// Runs `deflate` or `inflate` over as much of `input` and `output` as possible, returning
+    /// `Z_OK`, `Z_STREAM_END` or `Z_BUF_ERROR` when no progress was possible, and any other
+    /// result as an error.
+    pub(crate) fn code(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        flush: c_int,
+    ) -> Result<c_int> {
+        let stream = &mut *self.stream;
+
+        let next_in = input.unwritten();
+        let next_in = &next_in[..next_in.len().min(uInt::MAX as usize)];
+        let next_out = output.unwritten_mut();
+        let avail_out = next_out.len().min(uInt::MAX as usize);
+        stream.next_in = next_in.as_ptr() as *mut u8;
+        stream.avail_in = next_in.len() as uInt;
+        stream.next_out = next_out.as_mut_ptr();
+        stream.avail_out = avail_out as uInt;
+
+        let ret = unsafe {
+            match self.kind {
+                Kind::Deflate => deflate(stream, flush),
+                Kind::Inflate => inflate(stream, flush),
+            }
+        };
+
+        let read = next_in.len() - stream.avail_in as usize;
+        let written = avail_out - stream.avail_out as usize;
+        stream.next_in = ptr::null_mut();
+        stream.avail_in = 0;
+        stream.next_out = ptr::null_mut();
+        stream.avail_out = 0;
+
+        input.advance(read);
+        output.advance(written);
+
+        self.check(ret)
+    }
+
+    fn check(&self, ret: c_int) -> Result<c_int> {
+        match ret {
+            Z_OK | Z_STREAM_END | Z_BUF_ERROR => Ok(ret),
+            Z_NEED_DICT => Err(Error::new(
+                ErrorKind::InvalidData,
+                "stream requires a preset dictionary",
+            )),
+            Z_DATA_ERROR => Err(Error::new(ErrorKind::InvalidData, self.message())),
+            Z_STREAM_ERROR => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid zlib parameters",
+            )),
+            Z_MEM_ERROR => Err(Error::new(ErrorKind::Other, "out of memory")),
+            _ => Err(Error::new(ErrorKind::Other, "zlib error")),
+        }
+    }
+
+    /// zlib's description of the last error.
+    fn message(&self) -> String {
+        if self.stream.msg.is_null() {
+            return "corrupt deflate stream".into();
+        }
+        unsafe { CStr::from_ptr(self.stream.msg) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        unsafe {
+            match self.kind {
+                Kind::Deflate => deflateEnd(&mut *self.stream),
+                Kind::Inflate => inflateEnd(&mut *self.stream),
+            };
+        }
+    }
+}
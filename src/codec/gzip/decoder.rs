@@ -1,9 +1,7 @@
 use crate::{
-    codec::{
-        gzip::header::{self, Header},
-        Decode,
-    },
+    codec::{gzip::header, Decode},
     util::PartialBuffer,
+    GzipHeader,
 };
 use std::io::{Error, ErrorKind, Result};
 
@@ -22,7 +20,7 @@ pub struct GzipDecoder {
     inner: crate::codec::FlateDecoder,
     crc: Crc,
     state: State,
-    header: Header,
+    header: Option<GzipHeader>,
 }
 
 fn check_footer(crc: &Crc, input: &[u8]) -> Result<()> {
@@ -59,10 +57,19 @@ impl GzipDecoder {
             inner: crate::codec::FlateDecoder::new(false),
             crc: Crc::new(),
             state: State::Header(header::Parser::default()),
-            header: Header::default(),
+            header: None,
         }
     }
 
+    pub(crate) fn window_bits(&mut self, bits: u32) {
+        self.inner.window_bits(bits);
+    }
+
+    /// The header of the current member, kept until the next member's header is parsed.
+    pub(crate) fn header(&self) -> Option<&GzipHeader> {
+        self.header.as_ref()
+    }
+
     fn process<I: AsRef<[u8]>, O: AsRef<[u8]> + AsMut<[u8]>>(
         &mut self,
         input: &mut PartialBuffer<I>,
@@ -73,7 +80,7 @@ impl GzipDecoder {
             match &mut self.state {
                 State::Header(parser) => {
                     if let Some(header) = parser.input(input)? {
-                        self.header = header;
+                        self.header = Some(header);
                         self.state = State::Decoding;
                     }
                 }
@@ -115,7 +122,6 @@ impl Decode for GzipDecoder {
         self.inner.reinit()?;
         self.crc = Crc::new();
         self.state = State::Header(header::Parser::default());
-        self.header = Header::default();
         Ok(())
     }
 
@@ -167,4 +173,8 @@ impl Decode for GzipDecoder {
             ))
         }
     }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        crate::codec::match_signature(&[0x1f, 0x8b], input)
+    }
 }
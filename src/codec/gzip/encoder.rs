@@ -1,4 +1,4 @@
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{codec::Encode, util::PartialBuffer, GzipHeader};
 use std::io::Result;
 
 use flate2::{Compression, Crc};
@@ -18,7 +18,7 @@ pub struct GzipEncoder {
     state: State,
 }
 
-fn header(level: Compression) -> Vec<u8> {
+pub(super) fn header(level: Compression, header: &GzipHeader) -> Vec<u8> {
     let level_byte = if level.level() >= Compression::best().level() {
         0x02
     } else if level.level() <= Compression::fast().level() {
@@ -27,15 +27,58 @@ fn header(level: Compression) -> Vec<u8> {
         0x00
     };
 
-    vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, level_byte, 0xff]
+    let flags = u8::from(header.text)
+        | u8::from(header.header_crc) << 1
+        | u8::from(header.extra.is_some()) << 2
+        | u8::from(header.filename.is_some()) << 3
+        | u8::from(header.comment.is_some()) << 4;
+
+    let mut output = vec![0x1f, 0x8b, 0x08, flags];
+    output.extend(&header.mtime.to_le_bytes());
+    output.extend(&[level_byte, header.operating_system]);
+
+    if let Some(extra) = &header.extra {
+        output.extend(&(extra.len() as u16).to_le_bytes());
+        output.extend(extra);
+    }
+
+    if let Some(filename) = &header.filename {
+        output.extend(filename);
+        output.push(0);
+    }
+
+    if let Some(comment) = &header.comment {
+        output.extend(comment);
+        output.push(0);
+    }
+
+    if header.header_crc {
+        let mut crc = Crc::new();
+        crc.update(&output);
+        output.extend(&(crc.sum() as u16).to_le_bytes());
+    }
+
+    output
 }
 
 impl GzipEncoder {
     pub(crate) fn new(level: Compression) -> Self {
+        Self::with_header(level, &GzipHeader::default())
+    }
+
+    pub(crate) fn with_header(level: Compression, header: &GzipHeader) -> Self {
+        Self::with_header_and_params(level, header, crate::FlateParams::default())
+    }
+
+    pub(crate) fn with_header_and_params(
+        level: Compression,
+        header: &GzipHeader,
+        params: crate::FlateParams,
+    ) -> Self {
         Self {
-            inner: crate::codec::FlateEncoder::new(level, false),
+            inner: crate::codec::FlateEncoder::with_params(level, false, params),
             crc: Crc::new(),
-            state: State::Header(header(level).into()),
+            state: State::Header(self::header(level, header).into()),
         }
     }
 
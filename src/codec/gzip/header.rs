@@ -1,6 +1,13 @@
-use crate::util::PartialBuffer;
+use crate::{util::PartialBuffer, GzipHeader};
 use std::io::{Error, ErrorKind, Result};
 
+use flate2::Crc;
+
+/// The longest filename or comment accepted, not counting the terminating zero byte, so a header
+/// missing the terminator can't make the parser buffer the whole stream. This serves the same
+/// purpose as zlib's `name_max` and `comm_max`.
+const MAX_STRING_LEN: usize = 64 * 1024;
+
 #[derive(Debug, Default)]
 struct Flags {
     ascii: bool,
@@ -10,11 +17,6 @@ struct Flags {
     comment: bool,
 }
 
-#[derive(Debug, Default)]
-pub(super) struct Header {
-    flags: Flags,
-}
-
 #[derive(Debug)]
 enum State {
     Fixed(PartialBuffer<[u8; 10]>),
@@ -32,13 +34,27 @@ impl Default for State {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(super) struct Parser {
     state: State,
-    header: Header,
+    flags: Flags,
+    header: GzipHeader,
+    /// Checksum of the header bytes parsed so far, for verifying `FHCRC`.
+    crc: Crc,
 }
 
-impl Header {
+impl Default for Parser {
+    fn default() -> Self {
+        Self {
+            state: State::default(),
+            flags: Flags::default(),
+            header: GzipHeader::default(),
+            crc: Crc::new(),
+        }
+    }
+}
+
+impl Flags {
     fn parse(input: &[u8; 10]) -> Result<Self> {
         if input[0..3] != [0x1f, 0x8b, 0x08] {
             return Err(Error::new(ErrorKind::InvalidData, "Invalid gzip header"));
@@ -46,7 +62,7 @@ impl Header {
 
         let flag = input[3];
 
-        let flags = Flags {
+        let flags = Self {
             ascii: (flag & 0b0000_0001) != 0,
             crc: (flag & 0b0000_0010) != 0,
             extra: (flag & 0b0000_0100) != 0,
@@ -54,22 +70,63 @@ impl Header {
             comment: (flag & 0b0001_0000) != 0,
         };
 
-        Ok(Header { flags })
+        Ok(flags)
+    }
+}
+
+impl GzipHeader {
+    fn parse(input: &[u8; 10]) -> Self {
+        GzipHeader {
+            text: (input[3] & 0b0000_0001) != 0,
+            mtime: u32::from_le_bytes([input[4], input[5], input[6], input[7]]),
+            extra_flags: input[8],
+            operating_system: input[9],
+            header_crc: (input[3] & 0b0000_0010) != 0,
+            ..GzipHeader::default()
+        }
     }
 }
 
+/// Moves bytes from `input` to `data` up to a zero byte, which is consumed but not stored.
+/// Returns whether the zero byte was found.
+fn read_string(
+    data: &mut Vec<u8>,
+    input: &mut PartialBuffer<impl AsRef<[u8]>>,
+    name: &str,
+) -> Result<bool> {
+    let (len, found) = match memchr::memchr(0, input.unwritten()) {
+        Some(len) => (len, true),
+        None => (input.unwritten().len(), false),
+    };
+
+    if data.len() + len > MAX_STRING_LEN {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("gzip header {} is too long", name),
+        ));
+    }
+
+    data.extend_from_slice(&input.unwritten()[..len]);
+    input.advance(len + found as usize);
+
+    Ok(found)
+}
+
 impl Parser {
     pub(super) fn input(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
-    ) -> Result<Option<Header>> {
+    ) -> Result<Option<GzipHeader>> {
         loop {
             match &mut self.state {
                 State::Fixed(data) => {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
-                        self.header = Header::parse(&data.take().into_inner())?;
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        self.flags = Flags::parse(&data)?;
+                        self.header = GzipHeader::parse(&data);
                         self.state = State::ExtraLen(<_>::default());
                     } else {
                         return Ok(None);
@@ -77,7 +134,7 @@ impl Parser {
                 }
 
                 State::ExtraLen(data) => {
-                    if !self.header.flags.extra {
+                    if !self.flags.extra {
                         self.state = State::Filename(<_>::default());
                         continue;
                     }
@@ -85,7 +142,9 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
-                        let len = u16::from_be_bytes(data.take().into_inner());
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        let len = u16::from_le_bytes(data);
                         self.state = State::Extra(vec![0; usize::from(len)].into());
                     } else {
                         return Ok(None);
@@ -96,6 +155,9 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        self.header.extra = Some(data);
                         self.state = State::Filename(<_>::default());
                     } else {
                         return Ok(None);
@@ -103,41 +165,39 @@ impl Parser {
                 }
 
                 State::Filename(data) => {
-                    if !self.header.flags.filename {
+                    if !self.flags.filename {
                         self.state = State::Comment(<_>::default());
                         continue;
                     }
 
-                    if let Some(len) = memchr::memchr(0, input.unwritten()) {
-                        data.extend_from_slice(&input.unwritten()[..len]);
-                        input.advance(len + 1);
+                    if read_string(data, input, "filename")? {
+                        self.crc.update(data);
+                        self.crc.update(&[0]);
+                        self.header.filename = Some(std::mem::take(data));
                         self.state = State::Comment(<_>::default());
                     } else {
-                        data.extend_from_slice(input.unwritten());
-                        input.advance(input.unwritten().len());
                         return Ok(None);
                     }
                 }
 
                 State::Comment(data) => {
-                    if !self.header.flags.comment {
+                    if !self.flags.comment {
                         self.state = State::Crc(<_>::default());
                         continue;
                     }
 
-                    if let Some(len) = memchr::memchr(0, input.unwritten()) {
-                        data.extend_from_slice(&input.unwritten()[..len]);
-                        input.advance(len + 1);
+                    if read_string(data, input, "comment")? {
+                        self.crc.update(data);
+                        self.crc.update(&[0]);
+                        self.header.comment = Some(std::mem::take(data));
                         self.state = State::Crc(<_>::default());
                     } else {
-                        data.extend_from_slice(input.unwritten());
-                        input.advance(input.unwritten().len());
                         return Ok(None);
                     }
                 }
 
                 State::Crc(data) => {
-                    if !self.header.flags.crc {
+                    if !self.flags.crc {
                         self.state = State::Done;
                         return Ok(Some(std::mem::take(&mut self.header)));
                     }
@@ -145,6 +205,13 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
+                        let expected = u16::from_le_bytes(data.take().into_inner());
+                        if expected != self.crc.sum() as u16 {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                "CRC computed for header does not match",
+                            ));
+                        }
                         self.state = State::Done;
                         return Ok(Some(std::mem::take(&mut self.header)));
                     } else {
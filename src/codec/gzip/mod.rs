@@ -1,5 +1,10 @@
 mod decoder;
 mod encoder;
 mod header;
+mod parallel;
 
-pub(crate) use self::{decoder::GzipDecoder, encoder::GzipEncoder};
+pub(crate) use self::{
+    decoder::GzipDecoder,
+    encoder::GzipEncoder,
+    parallel::{ParallelGzipEncoder, DEFAULT_BLOCK_SIZE as PARALLEL_GZIP_BLOCK_SIZE},
+};
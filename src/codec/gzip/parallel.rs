@@ -0,0 +1,385 @@
+use crate::{
+    codec::{Encode, FlateEncoder},
+    unshared::Unshared,
+    util::PartialBuffer,
+    GzipHeader,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{Error, ErrorKind, Result},
+    mem,
+    sync::{
+        mpsc::{self, Receiver, Sender, SyncSender, TryRecvError},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use crc32fast::Hasher;
+use flate2::Compression;
+
+/// The furthest back deflate can reference, each block is primed with this much of the data
+/// preceding it.
+const WINDOW_SIZE: usize = 32 * 1024;
+
+pub(crate) const DEFAULT_BLOCK_SIZE: usize = 128 * 1024;
+
+struct Job {
+    level: Compression,
+    dictionary: Vec<u8>,
+    input: Vec<u8>,
+    last: bool,
+    result: SyncSender<Result<Block>>,
+    /// Woken once the result has been sent, so an encoder waiting on it can continue.
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+struct Block {
+    output: Vec<u8>,
+    crc: Hasher,
+}
+
+impl Job {
+    fn run(&self) -> Result<Block> {
+        let mut encoder = FlateEncoder::new(self.level, false);
+        if !self.dictionary.is_empty() {
+            encoder.set_dictionary(&self.dictionary)?;
+        }
+
+        let mut output = Vec::with_capacity(self.input.len() / 2 + 64);
+        let mut buffer = vec![0; 64 * 1024];
+
+        let mut input = PartialBuffer::new(&self.input[..]);
+        while !input.unwritten().is_empty() {
+            let mut chunk = PartialBuffer::new(&mut buffer[..]);
+            encoder.encode(&mut input, &mut chunk)?;
+            output.extend_from_slice(chunk.written());
+        }
+
+        // The sync flush leaves the stream on a byte boundary, so the next block's output can
+        // follow this block's directly
+        loop {
+            let mut chunk = PartialBuffer::new(&mut buffer[..]);
+            let done = if self.last {
+                encoder.finish(&mut chunk)?
+            } else {
+                encoder.flush(&mut chunk)?
+            };
+            output.extend_from_slice(chunk.written());
+            if done {
+                break;
+            }
+        }
+
+        let mut crc = Hasher::new();
+        crc.update(&self.input);
+
+        Ok(Block { output, crc })
+    }
+}
+
+fn worker(jobs: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        let job = match jobs.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        let _ = job.result.send(job.run());
+        let waker = job.waker.lock().unwrap().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Worker threads shared by every parallel encoder, started as encoders ask for them and then
+/// kept for reuse. Encoders never ask for more than the number of cores.
+struct Pool {
+    jobs: Sender<Job>,
+    receiver: Arc<Mutex<Receiver<Job>>>,
+    workers: usize,
+}
+
+static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+
+/// Queues `job` on the shared pool, first growing the pool to at least `threads` workers, which
+/// must be bounded by the caller.
+fn submit(job: Job, threads: usize) -> Result<()> {
+    let mut pool = POOL.lock().unwrap_or_else(|err| err.into_inner());
+    let pool = pool.get_or_insert_with(|| {
+        let (jobs, receiver) = mpsc::channel();
+        Pool {
+            jobs,
+            receiver: Arc::new(Mutex::new(receiver)),
+            workers: 0,
+        }
+    });
+
+    while pool.workers < threads {
+        let receiver = pool.receiver.clone();
+        thread::Builder::new()
+            .name("async-compression-gzip".into())
+            .spawn(move || worker(receiver))?;
+        pool.workers += 1;
+    }
+
+    pool.jobs
+        .send(job)
+        .map_err(|_| Error::new(ErrorKind::Other, "gzip worker threads exited"))
+}
+
+#[derive(Debug)]
+enum State {
+    Encoding,
+    Finishing,
+    Footer(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
+/// Splits the input into blocks which are compressed independently by worker threads and then
+/// written out in order as a single gzip member, like `pigz`.
+///
+/// Results are only ever polled for, when the next one is needed and not yet complete the
+/// encoder reports that it's waiting through `poll_wait` and the worker wakes the task.
+pub struct ParallelGzipEncoder {
+    level: Compression,
+    threads: usize,
+    block_size: usize,
+    /// The results of the blocks sent to the workers, in the order they must be written.
+    pending: Unshared<VecDeque<Receiver<Result<Block>>>>,
+    /// Whether the last call stopped because the oldest pending block is not complete.
+    waiting: bool,
+    waker: Arc<Mutex<Option<Waker>>>,
+    /// The header and then the output of each completed block while it's being written.
+    output: PartialBuffer<Vec<u8>>,
+    /// Input collected for the next block.
+    input: Vec<u8>,
+    /// The last `WINDOW_SIZE` bytes of input sent to the workers.
+    dictionary: Vec<u8>,
+    crc: Hasher,
+    /// The total input size modulo 2^32, as stored in the footer.
+    size: u32,
+    state: State,
+}
+
+impl ParallelGzipEncoder {
+    pub(crate) fn new(level: Compression, threads: usize, block_size: usize) -> Self {
+        // The pool is shared and never shrinks, so it's kept to one worker per core however many
+        // threads are asked for
+        let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+
+        Self {
+            level,
+            threads: threads.clamp(1, cores),
+            block_size: block_size.max(1),
+            pending: Unshared::new(VecDeque::new()),
+            waiting: false,
+            waker: Arc::new(Mutex::new(None)),
+            output: super::encoder::header(level, &GzipHeader::default()).into(),
+            input: Vec::new(),
+            dictionary: Vec::new(),
+            crc: Hasher::new(),
+            size: 0,
+            state: State::Encoding,
+        }
+    }
+
+    /// Sends the collected input to the workers as the next block.
+    fn send(&mut self, last: bool) -> Result<()> {
+        let input = mem::take(&mut self.input);
+        let dictionary = self.dictionary.clone();
+        self.size = self.size.wrapping_add(input.len() as u32);
+
+        if input.len() >= WINDOW_SIZE {
+            self.dictionary.clear();
+            self.dictionary
+                .extend_from_slice(&input[input.len() - WINDOW_SIZE..]);
+        } else {
+            self.dictionary.extend_from_slice(&input);
+            let excess = self.dictionary.len().saturating_sub(WINDOW_SIZE);
+            self.dictionary.drain(..excess);
+        }
+
+        let (result, receiver) = mpsc::sync_channel(1);
+        let job = Job {
+            level: self.level,
+            dictionary,
+            input,
+            last,
+            result,
+            waker: self.waker.clone(),
+        };
+        submit(job, self.threads)?;
+        self.pending.get_mut().push_back(receiver);
+
+        Ok(())
+    }
+
+    /// Takes the output of the oldest pending block if it's complete, returns whether a block was
+    /// taken.
+    fn receive(&mut self) -> Result<bool> {
+        let pending = self.pending.get_mut();
+
+        let result = match pending.front().map(Receiver::try_recv) {
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Empty)) | None => return Ok(false),
+            Some(Err(TryRecvError::Disconnected)) => {
+                return Err(Error::new(ErrorKind::Other, "gzip worker thread panicked"))
+            }
+        };
+        pending.pop_front();
+
+        let block = result?;
+        self.crc.combine(&block.crc);
+        self.output = block.output.into();
+
+        Ok(true)
+    }
+
+    /// Writes out as much completed output as possible, returns whether everything sent to the
+    /// workers so far has been written. If not and there is still space in `output`, the oldest
+    /// pending block isn't complete yet and the encoder is left waiting on it.
+    fn drain(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.waiting = false;
+
+        loop {
+            output.copy_unwritten_from(&mut self.output);
+
+            if !self.output.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            if !self.receive()? {
+                let done = self.pending.get_mut().is_empty();
+                self.waiting = !done;
+                return Ok(done);
+            }
+        }
+    }
+
+    fn footer(&mut self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(8);
+
+        output.extend(&self.crc.clone().finalize().to_le_bytes());
+        output.extend(&self.size.to_le_bytes());
+
+        output
+    }
+}
+
+impl Encode for ParallelGzipEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        match self.state {
+            State::Encoding => {}
+            State::Finishing | State::Footer(_) | State::Done => panic!("encode after complete"),
+        }
+
+        loop {
+            self.drain(output)?;
+            // Only a full set of pending blocks is worth waiting on while there is input
+            self.waiting = false;
+
+            let len = std::cmp::min(self.block_size - self.input.len(), input.unwritten().len());
+            self.input.extend_from_slice(&input.unwritten()[..len]);
+            input.advance(len);
+
+            if self.input.len() < self.block_size {
+                return Ok(());
+            }
+
+            if self.pending.get_mut().len() < self.threads * 2 {
+                self.send(false)?;
+            } else if output.unwritten().is_empty() {
+                return Ok(());
+            } else if !self.receive()? {
+                // Every worker is busy, wait for the oldest block to make room for this one
+                self.waiting = true;
+                return Ok(());
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match self.state {
+            State::Encoding => {
+                if !self.input.is_empty() {
+                    self.send(false)?;
+                }
+                self.drain(output)
+            }
+            State::Finishing | State::Footer(_) | State::Done => self.finish(output),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Encoding => {
+                    self.send(true)?;
+                    self.state = State::Finishing;
+                }
+
+                State::Finishing => {
+                    if !self.drain(output)? {
+                        return Ok(false);
+                    }
+                    self.state = State::Footer(self.footer().into());
+                }
+
+                State::Footer(footer) => {
+                    output.copy_unwritten_from(&mut *footer);
+
+                    if !footer.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+                    self.state = State::Done;
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if !self.waiting {
+            return Poll::Ready(Ok(()));
+        }
+
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        // The block may have completed before the waker was registered, in which case its worker
+        // found nothing to wake
+        if self.receive()? {
+            self.waiting = false;
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for ParallelGzipEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelGzipEncoder")
+            .field("level", &self.level)
+            .field("threads", &self.threads)
+            .field("block_size", &self.block_size)
+            .field("state", &self.state)
+            .finish()
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{codec::Decode, error::LimitExceeded, util::PartialBuffer};
+use std::io::Result;
+
+/// Wraps a decoder to enforce the output limits configurable on the generic decoders.
+#[derive(Debug)]
+pub(crate) struct Limited<D> {
+    inner: D,
+    max_output_size: Option<u64>,
+    max_ratio: Option<u64>,
+    input_len: u64,
+    output_len: u64,
+}
+
+impl<D: Decode> Limited<D> {
+    pub(crate) fn new(inner: D) -> Self {
+        Self {
+            inner,
+            max_output_size: None,
+            max_ratio: None,
+            input_len: 0,
+            output_len: 0,
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &D {
+        &self.inner
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut D {
+        &mut self.inner
+    }
+
+    pub(crate) fn max_output_size(&mut self, limit: Option<u64>) {
+        self.max_output_size = limit;
+    }
+
+    pub(crate) fn max_ratio(&mut self, limit: Option<u64>) {
+        self.max_ratio = limit;
+    }
+
+    /// Runs `f` with `output` truncated to one byte more than the remaining output allowance, so
+    /// that exceeding it is detected without producing an unbounded amount of output first.
+    fn limit_output<R>(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        f: impl FnOnce(&mut D, &mut PartialBuffer<&mut [u8]>) -> Result<R>,
+    ) -> Result<R> {
+        let mut len = output.unwritten().len();
+        if let Some(limit) = self.max_output_size {
+            let allowance = limit.saturating_sub(self.output_len).saturating_add(1);
+            if allowance < len as u64 {
+                len = allowance as usize;
+            }
+        }
+
+        let mut limited = PartialBuffer::new(&mut output.unwritten_mut()[..len]);
+        let result = f(&mut self.inner, &mut limited)?;
+        let written = limited.written().len();
+        output.advance(written);
+        self.output_len += written as u64;
+
+        Ok(result)
+    }
+
+    fn check(&self) -> Result<()> {
+        if let Some(limit) = self.max_output_size {
+            if self.output_len > limit {
+                return Err(LimitExceeded::OutputSize(limit).into());
+            }
+        }
+
+        if let Some(limit) = self.max_ratio {
+            if self.output_len > self.input_len.saturating_mul(limit) {
+                return Err(LimitExceeded::Ratio(limit).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<D: Decode> Decode for Limited<D> {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let prior = input.written().len();
+        let done = self.limit_output(output, |inner, output| inner.decode(input, output))?;
+        self.input_len += (input.written().len() - prior) as u64;
+        self.check()?;
+        Ok(done)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let done = self.limit_output(output, |inner, output| inner.flush(output))?;
+        self.check()?;
+        Ok(done)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let done = self.limit_output(output, |inner, output| inner.finish(output))?;
+        self.check()?;
+        Ok(done)
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        self.inner.is_member_start(input)
+    }
+}
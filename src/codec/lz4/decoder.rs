@@ -0,0 +1,117 @@
+use crate::{codec::Decode, unshared::Unshared, util::PartialBuffer};
+use core::ptr;
+use std::io::{Error, ErrorKind, Result};
+
+use lz4::liblz4::{
+    check_error, LZ4FDecompressionContext, LZ4F_createDecompressionContext, LZ4F_decompress,
+    LZ4F_freeDecompressionContext, LZ4F_resetDecompressionContext, LZ4F_VERSION,
+};
+
+struct Context(LZ4FDecompressionContext);
+
+impl Context {
+    fn new() -> Result<Self> {
+        let mut context = LZ4FDecompressionContext(ptr::null_mut());
+        check_error(unsafe { LZ4F_createDecompressionContext(&mut context, LZ4F_VERSION) })?;
+        Ok(Self(context))
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { LZ4F_freeDecompressionContext(self.0) };
+    }
+}
+
+#[derive(Debug)]
+pub struct Lz4Decoder {
+    context: Unshared<Context>,
+    done: bool,
+}
+
+impl Lz4Decoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            context: Unshared::new(Context::new().unwrap()),
+            done: false,
+        }
+    }
+
+    /// Returns whether the end of the frame has been reached.
+    fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<(usize, bool)> {
+        let out = output.unwritten_mut();
+        let mut out_len = out.len();
+        let mut in_len = input.len();
+        let remaining = check_error(unsafe {
+            LZ4F_decompress(
+                self.context.get_mut().0,
+                out.as_mut_ptr(),
+                &mut out_len,
+                input.as_ptr(),
+                &mut in_len,
+                ptr::null(),
+            )
+        })?;
+        output.advance(out_len);
+        Ok((in_len, remaining == 0))
+    }
+}
+
+impl Decode for Lz4Decoder {
+    fn reinit(&mut self) -> Result<()> {
+        unsafe { LZ4F_resetDecompressionContext(self.context.get_mut().0) };
+        self.done = false;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let (len, done) = self.decompress(input.unwritten(), output)?;
+        input.advance(len);
+        self.done = done;
+        Ok(done)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.done {
+            return Ok(true);
+        }
+
+        // Decoded data that didn't fit in the output previously is kept by `liblz4` and returned
+        // on the next call.
+        self.decompress(&[], output)?;
+        Ok(!output.unwritten().is_empty())
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.done {
+            return Ok(true);
+        }
+
+        if !self.flush(output)? {
+            return Ok(false);
+        }
+
+        Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "unexpected end of file",
+        ))
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        crate::codec::match_frame_signature([0x04, 0x22, 0x4d, 0x18], input)
+    }
+}
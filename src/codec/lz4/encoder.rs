@@ -0,0 +1,189 @@
+use crate::{codec::Encode, unshared::Unshared, util::PartialBuffer};
+use core::ptr;
+use std::io::Result;
+
+use lz4::liblz4::{
+    check_error, LZ4FCompressionContext, LZ4FPreferences, LZ4F_compressBegin, LZ4F_compressBound,
+    LZ4F_compressEnd, LZ4F_compressUpdate, LZ4F_createCompressionContext, LZ4F_flush,
+    LZ4F_freeCompressionContext, LZ4F_VERSION,
+};
+
+/// Maximum size of a frame header, `LZ4F_HEADER_SIZE_MAX` in `lz4frame.h`.
+const HEADER_SIZE_MAX: usize = 19;
+
+#[derive(Debug)]
+enum State {
+    Header,
+    Encoding,
+    Done,
+}
+
+struct Context(LZ4FCompressionContext);
+
+impl Context {
+    fn new() -> Result<Self> {
+        let mut context = LZ4FCompressionContext(ptr::null_mut());
+        check_error(unsafe { LZ4F_createCompressionContext(&mut context, LZ4F_VERSION) })?;
+        Ok(Self(context))
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { LZ4F_freeCompressionContext(self.0) };
+    }
+}
+
+/// `liblz4` requires the output buffer to be large enough for the worst case of every call, so
+/// output is produced into `buffer` first then copied out as space becomes available.
+#[derive(Debug)]
+pub struct Lz4Encoder {
+    context: Unshared<Context>,
+    preferences: LZ4FPreferences,
+    block_size: usize,
+    buffer: PartialBuffer<Vec<u8>>,
+    state: State,
+}
+
+impl Lz4Encoder {
+    pub(crate) fn new(preferences: LZ4FPreferences) -> Self {
+        Self {
+            context: Unshared::new(Context::new().unwrap()),
+            block_size: preferences.frame_info.block_size_id.get_size(),
+            preferences,
+            buffer: PartialBuffer::new(Vec::new()),
+            state: State::Header,
+        }
+    }
+
+    /// Runs `f` with an empty buffer of `capacity` bytes, `f` returns how much of it was filled.
+    fn write(
+        &mut self,
+        capacity: usize,
+        f: impl FnOnce(LZ4FCompressionContext, &mut [u8]) -> usize,
+    ) -> Result<()> {
+        let mut buffer = self.buffer.take().into_inner();
+        buffer.clear();
+        buffer.resize(capacity, 0);
+        let len = check_error(f(self.context.get_mut().0, &mut buffer))?;
+        buffer.truncate(len);
+        self.buffer = PartialBuffer::new(buffer);
+        Ok(())
+    }
+
+    fn bound(&self, len: usize) -> usize {
+        unsafe { LZ4F_compressBound(len, &self.preferences) }
+    }
+
+    fn begin(&mut self) -> Result<()> {
+        let preferences: *const LZ4FPreferences = &self.preferences;
+        self.write(HEADER_SIZE_MAX, |context, buffer| unsafe {
+            LZ4F_compressBegin(context, buffer.as_mut_ptr(), buffer.len(), preferences)
+        })
+    }
+
+    /// Copies out any buffered output, returns whether it was all written.
+    fn drain(&mut self, output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>) -> bool {
+        output.copy_unwritten_from(&mut self.buffer);
+        self.buffer.unwritten().is_empty()
+    }
+}
+
+impl Encode for Lz4Encoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            if !self.drain(output) {
+                return Ok(());
+            }
+
+            match self.state {
+                State::Header => {
+                    self.begin()?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    let len = std::cmp::min(input.unwritten().len(), self.block_size);
+                    if len == 0 {
+                        return Ok(());
+                    }
+
+                    let src = &input.unwritten()[..len];
+                    self.write(self.bound(len), |context, buffer| unsafe {
+                        LZ4F_compressUpdate(
+                            context,
+                            buffer.as_mut_ptr(),
+                            buffer.len(),
+                            src.as_ptr(),
+                            src.len(),
+                            ptr::null(),
+                        )
+                    })?;
+                    input.advance(len);
+                }
+
+                State::Done => panic!("encode after complete"),
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            if !self.drain(output) {
+                return Ok(false);
+            }
+
+            match self.state {
+                State::Header => {
+                    self.begin()?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    self.write(self.bound(0), |context, buffer| unsafe {
+                        LZ4F_flush(context, buffer.as_mut_ptr(), buffer.len(), ptr::null())
+                    })?;
+                    if self.buffer.unwritten().is_empty() {
+                        return Ok(true);
+                    }
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            if !self.drain(output) {
+                return Ok(false);
+            }
+
+            match self.state {
+                State::Header => {
+                    self.begin()?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    self.write(self.bound(0), |context, buffer| unsafe {
+                        LZ4F_compressEnd(context, buffer.as_mut_ptr(), buffer.len(), ptr::null())
+                    })?;
+                    self.state = State::Done;
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    codec::{
+        lzip::{decode_dict_size, HEADER_SIZE, MAGIC, TRAILER_SIZE, VERSION},
+        lzma_raw::{self, RawFilter, RawStream},
+        Decode,
+    },
+    util::PartialBuffer,
+};
+use std::io::{Error, ErrorKind, Result};
+
+use crc32fast::Hasher;
+
+#[derive(Debug)]
+enum State {
+    Header(PartialBuffer<Vec<u8>>),
+    Decoding(RawStream),
+    Trailer(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
+#[derive(Debug)]
+pub struct LzipDecoder {
+    crc: Hasher,
+    data_size: u64,
+    /// The size of the LZMA data, known once it has been decoded.
+    compressed_size: u64,
+    state: State,
+}
+
+fn parse_header(input: &[u8]) -> Result<RawStream> {
+    if input[..4] != MAGIC[..] {
+        return Err(Error::new(ErrorKind::InvalidData, "Invalid lzip header"));
+    }
+
+    if input[4] != VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Unsupported lzip version",
+        ));
+    }
+
+    let dict_size = decode_dict_size(input[5])
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid lzip dictionary size"))?;
+
+    // Every lzip member uses the same literal and position bits as the default preset
+    let mut options = lzma_raw::preset_options(0)?;
+    options.dict_size = dict_size;
+    RawStream::decoder(RawFilter::Lzma1, &mut options)
+}
+
+fn check_trailer(crc: &Hasher, data_size: u64, compressed_size: u64, input: &[u8]) -> Result<()> {
+    let member_size = (HEADER_SIZE + TRAILER_SIZE) as u64 + compressed_size;
+
+    if crc.clone().finalize().to_le_bytes() != input[0..4] {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "CRC computed does not match",
+        ));
+    }
+
+    if data_size.to_le_bytes() != input[4..12] {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "amount of bytes read does not match",
+        ));
+    }
+
+    if member_size.to_le_bytes() != input[12..20] {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "member size does not match",
+        ));
+    }
+
+    Ok(())
+}
+
+impl LzipDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            crc: Hasher::new(),
+            data_size: 0,
+            compressed_size: 0,
+            state: State::Header(vec![0; HEADER_SIZE].into()),
+        }
+    }
+}
+
+impl Decode for LzipDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        *self = Self::new();
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Header(header) => {
+                    header.copy_unwritten_from(input);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Decoding(parse_header(header.written())?);
+                    }
+                }
+
+                State::Decoding(stream) => {
+                    let prior = output.written().len();
+                    let done = stream.process(input, output, lzma_raw::RUN)?;
+                    let decoded = &output.written()[prior..];
+                    self.crc.update(decoded);
+                    self.data_size += decoded.len() as u64;
+
+                    if done {
+                        self.compressed_size = stream.total_in();
+                        self.state = State::Trailer(vec![0; TRAILER_SIZE].into());
+                    }
+                }
+
+                State::Trailer(trailer) => {
+                    trailer.copy_unwritten_from(input);
+
+                    if trailer.unwritten().is_empty() {
+                        check_trailer(
+                            &self.crc,
+                            self.data_size,
+                            self.compressed_size,
+                            trailer.written(),
+                        )?;
+                        self.state = State::Done;
+                    }
+                }
+
+                State::Done => {}
+            }
+
+            if let State::Done = self.state {
+                return Ok(true);
+            }
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // While decoding flush is a noop
+        Ok(true)
+    }
+
+    fn finish(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // Because of the trailer all the data has to have been output before we get here
+        if let State::Done = self.state {
+            Ok(true)
+        } else {
+            Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "unexpected end of file",
+            ))
+        }
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        crate::codec::match_signature(MAGIC, input)
+    }
+}
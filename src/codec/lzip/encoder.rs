@@ -0,0 +1,202 @@
+use crate::{
+    codec::{
+        lzip::{encode_dict_size, HEADER_SIZE, MAGIC, TRAILER_SIZE, VERSION},
+        lzma_raw::{self, RawFilter, RawStream},
+        Encode,
+    },
+    util::PartialBuffer,
+};
+use std::io::Result;
+
+use crc32fast::Hasher;
+
+#[derive(Debug)]
+enum State {
+    /// Between members, the next member is started once there is input for it.
+    Idle,
+    Header(PartialBuffer<Vec<u8>>),
+    Encoding,
+    Trailer(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
+/// liblzma's LZMA1 encoder can't flush without ending the stream, so each flush ends the current
+/// member and any further input is written as a new member.
+#[derive(Debug)]
+pub struct LzipEncoder {
+    level: u32,
+    coded_dict_size: u8,
+    dict_size: u32,
+    stream: RawStream,
+    crc: Hasher,
+    data_size: u64,
+    /// Whether a member has been started, empty input is still written as a single member.
+    started: bool,
+    state: State,
+}
+
+impl LzipEncoder {
+    pub(crate) fn new(level: u32) -> Self {
+        let options = lzma_raw::preset_options(level).unwrap();
+        let (coded_dict_size, dict_size) = encode_dict_size(options.dict_size);
+
+        Self {
+            level,
+            coded_dict_size,
+            dict_size,
+            stream: Self::stream(level, dict_size).unwrap(),
+            crc: Hasher::new(),
+            data_size: 0,
+            started: false,
+            state: State::Idle,
+        }
+    }
+
+    fn stream(level: u32, dict_size: u32) -> Result<RawStream> {
+        let mut options = lzma_raw::preset_options(level)?;
+        options.dict_size = dict_size;
+        RawStream::encoder(RawFilter::Lzma1, &mut options)
+    }
+
+    fn start_member(&mut self) -> Result<()> {
+        if self.started {
+            self.stream = Self::stream(self.level, self.dict_size)?;
+            self.crc = Hasher::new();
+            self.data_size = 0;
+        }
+        self.started = true;
+
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend(MAGIC);
+        header.extend(&[VERSION, self.coded_dict_size]);
+        self.state = State::Header(header.into());
+
+        Ok(())
+    }
+
+    fn trailer(&mut self) -> Vec<u8> {
+        let member_size = (HEADER_SIZE + TRAILER_SIZE) as u64 + self.stream.total_out();
+
+        let mut output = Vec::with_capacity(TRAILER_SIZE);
+        output.extend(&self.crc.clone().finalize().to_le_bytes());
+        output.extend(&self.data_size.to_le_bytes());
+        output.extend(&member_size.to_le_bytes());
+        output
+    }
+
+    /// Writes out the rest of the current member, returns whether it is complete.
+    fn end_member(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Idle | State::Done => return Ok(true),
+
+                State::Header(header) => {
+                    output.copy_unwritten_from(&mut *header);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Encoding;
+                    }
+                }
+
+                State::Encoding => {
+                    let input = &mut PartialBuffer::new(&[][..]);
+                    if self.stream.process(input, output, lzma_raw::FINISH)? {
+                        self.state = State::Trailer(self.trailer().into());
+                    }
+                }
+
+                State::Trailer(trailer) => {
+                    output.copy_unwritten_from(&mut *trailer);
+
+                    if trailer.unwritten().is_empty() {
+                        self.state = State::Idle;
+                    }
+                }
+            }
+
+            if let State::Idle = self.state {
+                return Ok(true);
+            }
+
+            if output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
+    }
+}
+
+impl Encode for LzipEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            match &mut self.state {
+                State::Idle => {
+                    if input.unwritten().is_empty() {
+                        return Ok(());
+                    }
+                    self.start_member()?;
+                }
+
+                State::Header(header) => {
+                    output.copy_unwritten_from(&mut *header);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Encoding;
+                    }
+                }
+
+                State::Encoding => {
+                    let prior_written = input.written().len();
+                    self.stream.process(input, output, lzma_raw::RUN)?;
+                    let consumed = &input.written()[prior_written..];
+                    self.crc.update(consumed);
+                    self.data_size += consumed.len() as u64;
+                }
+
+                State::Trailer(_) => {
+                    // The end of a member from a flush that didn't have space to complete
+                    self.end_member(output)?;
+                }
+
+                State::Done => panic!("encode after complete"),
+            };
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.end_member(output)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if let State::Idle = self.state {
+            if self.started {
+                self.state = State::Done;
+            } else {
+                self.start_member()?;
+            }
+        }
+
+        if self.end_member(output)? {
+            self.state = State::Done;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
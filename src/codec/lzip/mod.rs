@@ -0,0 +1,47 @@
+mod decoder;
+mod encoder;
+
+pub(crate) use self::{decoder::LzipDecoder, encoder::LzipEncoder};
+
+const MAGIC: &[u8; 4] = b"LZIP";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 6;
+/// The CRC32 and size of the uncompressed data, followed by the size of the whole member.
+const TRAILER_SIZE: usize = 20;
+
+const MIN_DICT_SIZE: u32 = 1 << 12;
+const MAX_DICT_SIZE: u32 = 1 << 29;
+
+/// Decodes the coded dictionary size from a member header, the low 5 bits are the base 2
+/// logarithm of a base size and the high 3 bits the number of sixteenths of it to subtract.
+fn decode_dict_size(coded: u8) -> Option<u32> {
+    let log = u32::from(coded & 0x1f);
+    let fraction = u32::from(coded >> 5);
+    if !(12..=29).contains(&log) {
+        return None;
+    }
+
+    let base = 1 << log;
+    let size = base - (base / 16) * fraction;
+    if size < MIN_DICT_SIZE {
+        return None;
+    }
+    Some(size)
+}
+
+/// Finds the smallest dictionary size at least `size` that a member header can represent.
+fn encode_dict_size(size: u32) -> (u8, u32) {
+    let size = size.clamp(MIN_DICT_SIZE, MAX_DICT_SIZE);
+    let log = 32 - (size - 1).leading_zeros();
+    let base = 1 << log;
+
+    let fraction = (0..8)
+        .rev()
+        .find(|fraction| base - (base / 16) * fraction >= size)
+        .unwrap_or(0);
+
+    (
+        log as u8 | (fraction as u8) << 5,
+        base - (base / 16) * fraction,
+    )
+}
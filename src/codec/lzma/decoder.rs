@@ -13,6 +13,10 @@ impl LzmaDecoder {
             inner: crate::codec::Xz2Decoder::new(),
         }
     }
+
+    pub fn max_memory_usage(&mut self, limit: Option<u64>) {
+        self.inner.max_memory_usage(limit);
+    }
 }
 
 impl Decode for LzmaDecoder {
@@ -0,0 +1,87 @@
+use crate::{
+    codec::{
+        lzma_raw::{self, RawFilter, RawStream},
+        Decode,
+    },
+    util::PartialBuffer,
+};
+use std::io::{Error, ErrorKind, Result};
+
+/// The dictionary size used by the highest preset, so data from any level can be decoded.
+pub(crate) const DEFAULT_DICT_SIZE: u32 = 64 << 20;
+
+#[derive(Debug)]
+pub struct Lzma2Decoder {
+    stream: RawStream,
+    dict_size: u32,
+    done: bool,
+}
+
+fn stream(dict_size: u32) -> Result<RawStream> {
+    let mut options = lzma_raw::preset_options(0)?;
+    options.dict_size = dict_size;
+    RawStream::decoder(RawFilter::Lzma2, &mut options)
+}
+
+impl Lzma2Decoder {
+    pub(crate) fn new() -> Self {
+        Self::with_dict_size(DEFAULT_DICT_SIZE).unwrap()
+    }
+
+    pub(crate) fn with_dict_size(dict_size: u32) -> Result<Self> {
+        Ok(Self {
+            stream: stream(dict_size)?,
+            dict_size,
+            done: false,
+        })
+    }
+}
+
+impl Decode for Lzma2Decoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.stream = stream(self.dict_size)?;
+        self.done = false;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.done = self.stream.process(input, output, lzma_raw::RUN)?;
+        Ok(self.done)
+    }
+
+    fn flush(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // While decoding flush is a noop
+        Ok(true)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.done {
+            return Ok(true);
+        }
+
+        let prior = output.written().len();
+        self.done =
+            self.stream
+                .process(&mut PartialBuffer::new(&[][..]), output, lzma_raw::FINISH)?;
+
+        if !self.done && output.written().len() == prior && !output.unwritten().is_empty() {
+            // No more input is coming and none of the buffered data could be output
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "unexpected end of file",
+            ));
+        }
+
+        Ok(self.done)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    codec::{
+        lzma_raw::{self, RawFilter, RawStream},
+        Encode,
+    },
+    util::PartialBuffer,
+};
+use std::io::Result;
+
+#[derive(Debug)]
+pub struct Lzma2Encoder {
+    stream: RawStream,
+}
+
+impl Lzma2Encoder {
+    pub(crate) fn new(level: u32) -> Self {
+        let mut options = lzma_raw::preset_options(level).unwrap();
+        Self {
+            stream: RawStream::encoder(RawFilter::Lzma2, &mut options).unwrap(),
+        }
+    }
+}
+
+impl Encode for Lzma2Encoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        self.stream.process(input, output, lzma_raw::RUN)?;
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.stream.process(
+            &mut PartialBuffer::new(&[][..]),
+            output,
+            lzma_raw::SYNC_FLUSH,
+        )
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.stream
+            .process(&mut PartialBuffer::new(&[][..]), output, lzma_raw::FINISH)
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{unshared::Unshared, util::PartialBuffer};
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+    mem,
+    os::raw::c_void,
+    ptr,
+};
+
+use lzma_sys::{
+    lzma_action, lzma_auto_decoder, lzma_code, lzma_end, lzma_filter, lzma_lzma_preset,
+    lzma_memlimit_set, lzma_memusage, lzma_options_lzma, lzma_raw_decoder, lzma_raw_encoder,
+    lzma_ret, lzma_stream, LZMA_BUF_ERROR, LZMA_DATA_ERROR, LZMA_FILTER_LZMA1, LZMA_FILTER_LZMA2,
+    LZMA_FORMAT_ERROR, LZMA_MEM_ERROR, LZMA_OK, LZMA_OPTIONS_ERROR, LZMA_STREAM_END,
+    LZMA_VLI_UNKNOWN,
+};
+
+pub(crate) use lzma_sys::{
+    LZMA_FINISH as FINISH, LZMA_FULL_FLUSH as FULL_FLUSH, LZMA_RUN as RUN,
+    LZMA_SYNC_FLUSH as SYNC_FLUSH,
+};
+
+/// Which LZMA variant a [`RawStream`] codes, with no container around the compressed data.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RawFilter {
+    /// LZMA1 data terminated by an end of payload marker, as stored in lzip members.
+    Lzma1,
+    /// LZMA2 chunks terminated by an end marker, as stored in xz blocks and 7z archives.
+    Lzma2,
+}
+
+/// Returns the LZMA options liblzma uses for `preset`, which always has the `lc`, `lp` and `pb`
+/// values lzip requires.
+pub(crate) fn preset_options(preset: u32) -> Result<lzma_options_lzma> {
+    // Safety: this is a plain C struct, zeroed is a valid value for all of its fields
+    let mut options: lzma_options_lzma = unsafe { mem::zeroed() };
+    if unsafe { lzma_lzma_preset(&mut options, preset) } != 0 {
+        return Err(Error::new(ErrorKind::InvalidInput, "unsupported preset"));
+    }
+    Ok(options)
+}
+
+/// Returned by the multithreaded encoder when it gave up waiting on its workers, from liblzma's
+/// `common.h`. `lzma_code` reports it as `LZMA_OK` but it's handled in case it ever leaks out.
+const LZMA_TIMED_OUT: lzma_ret = 32;
+
+pub(crate) fn check(ret: lzma_ret) -> Result<bool> {
+    match ret {
+        // `LZMA_BUF_ERROR` only means no progress could be made, and `LZMA_TIMED_OUT` that it was
+        // waiting on worker threads, callers check for that themselves
+        LZMA_OK | LZMA_BUF_ERROR | LZMA_TIMED_OUT => Ok(false),
+        LZMA_STREAM_END => Ok(true),
+        LZMA_MEM_ERROR => Err(Error::new(ErrorKind::Other, "out of memory")),
+        LZMA_OPTIONS_ERROR => Err(Error::new(ErrorKind::InvalidInput, "unsupported options")),
+        LZMA_FORMAT_ERROR | LZMA_DATA_ERROR => {
+            Err(Error::new(ErrorKind::InvalidData, "corrupt lzma data"))
+        }
+        _ => Err(Error::new(ErrorKind::Other, "liblzma error")),
+    }
+}
+
+/// An initialized liblzma stream, ended when dropped.
+pub(crate) struct Stream(lzma_stream);
+
+// liblzma streams may be used from any thread, just not from multiple threads at once, which
+// `Unshared` takes care of.
+unsafe impl Send for Stream {}
+
+impl Stream {
+    /// Creates a decoder for either the xz or lzma format, detected from the data, which fails
+    /// with `LZMA_MEMLIMIT_ERROR` once decoding needs more than `memlimit` bytes.
+    pub(crate) fn auto_decoder(memlimit: u64) -> Result<Self> {
+        Self::new(|stream| unsafe { lzma_auto_decoder(stream, memlimit, 0) })
+    }
+
+    /// Creates a stream set up by `init`, one of liblzma's encoder or decoder initializers.
+    pub(crate) fn new(init: impl FnOnce(&mut lzma_stream) -> lzma_ret) -> Result<Self> {
+        // Safety: a zeroed stream is `LZMA_STREAM_INIT`
+        let mut stream = Self(unsafe { mem::zeroed() });
+        check(init(&mut stream.0))?;
+        Ok(stream)
+    }
+
+    /// The number of compressed or uncompressed bytes written so far.
+    pub(crate) fn total_out(&self) -> u64 {
+        self.0.total_out
+    }
+
+    /// The number of compressed or uncompressed bytes read so far.
+    pub(crate) fn total_in(&self) -> u64 {
+        self.0.total_in
+    }
+
+    /// The memory in bytes the stream currently needs.
+    pub(crate) fn memusage(&self) -> u64 {
+        unsafe { lzma_memusage(&self.0) }
+    }
+
+    /// Changes the memory limit of a decoder, which fails with `LZMA_MEMLIMIT_ERROR` and keeps
+    /// the old limit if the stream already needs more than `limit`.
+    pub(crate) fn set_memlimit(&mut self, limit: u64) -> lzma_ret {
+        unsafe { lzma_memlimit_set(&mut self.0, limit) }
+    }
+
+    /// Runs the coder over as much of `input` and `output` as possible, returning liblzma's
+    /// result as is.
+    pub(crate) fn code(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        action: lzma_action,
+    ) -> lzma_ret {
+        let stream = &mut self.0;
+
+        let next_in = input.unwritten();
+        let next_out = output.unwritten_mut();
+        stream.next_in = next_in.as_ptr();
+        stream.avail_in = next_in.len();
+        stream.next_out = next_out.as_mut_ptr();
+        stream.avail_out = next_out.len();
+
+        let ret = unsafe { lzma_code(stream, action) };
+
+        let read = next_in.len() - stream.avail_in;
+        let written = next_out.len() - stream.avail_out;
+        stream.next_in = ptr::null();
+        stream.avail_in = 0;
+        stream.next_out = ptr::null_mut();
+        stream.avail_out = 0;
+
+        input.advance(read);
+        output.advance(written);
+
+        ret
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        unsafe { lzma_end(&mut self.0) };
+    }
+}
+
+/// A liblzma encoder or decoder for raw LZMA data, which `xz2` doesn't expose.
+pub(crate) struct RawStream {
+    stream: Unshared<Stream>,
+    filter: RawFilter,
+}
+
+impl RawStream {
+    pub(crate) fn encoder(filter: RawFilter, options: &mut lzma_options_lzma) -> Result<Self> {
+        Self::new(filter, options, lzma_raw_encoder)
+    }
+
+    pub(crate) fn decoder(filter: RawFilter, options: &mut lzma_options_lzma) -> Result<Self> {
+        Self::new(filter, options, lzma_raw_decoder)
+    }
+
+    fn new(
+        filter: RawFilter,
+        options: &mut lzma_options_lzma,
+        init: unsafe extern "C" fn(*mut lzma_stream, *const lzma_filter) -> lzma_ret,
+    ) -> Result<Self> {
+        let id = match filter {
+            RawFilter::Lzma1 => LZMA_FILTER_LZMA1,
+            RawFilter::Lzma2 => LZMA_FILTER_LZMA2,
+        };
+        let filters = [
+            lzma_filter {
+                id,
+                options: options as *mut lzma_options_lzma as *mut c_void,
+            },
+            lzma_filter {
+                id: LZMA_VLI_UNKNOWN,
+                options: ptr::null_mut(),
+            },
+        ];
+
+        // Safety: the options are copied during init so only need to live until it returns
+        let stream = Stream::new(|stream| unsafe { init(stream, filters.as_ptr()) })?;
+
+        Ok(Self {
+            stream: Unshared::new(stream),
+            filter,
+        })
+    }
+
+    /// The number of compressed or uncompressed bytes written so far.
+    pub(crate) fn total_out(&mut self) -> u64 {
+        self.stream.get_mut().total_out()
+    }
+
+    /// The number of compressed or uncompressed bytes read so far.
+    pub(crate) fn total_in(&mut self) -> u64 {
+        self.stream.get_mut().total_in()
+    }
+
+    /// Runs the coder over as much of `input` and `output` as possible, returns whether the end of
+    /// the stream or the requested flush was reached.
+    pub(crate) fn process(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        action: lzma_action,
+    ) -> Result<bool> {
+        check(self.stream.get_mut().code(input, output, action))
+    }
+}
+
+impl fmt::Debug for RawStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawStream")
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
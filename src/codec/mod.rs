@@ -1,49 +1,92 @@
+//! Traits describing the interface between the asynchronous IO adaptors and the underlying
+//! compression algorithms.
+//!
+//! Every encoder and decoder exported by this crate is built by wrapping one of the generic
+//! adaptors (such as [`futures::bufread::Encoder`](crate::futures::bufread::Encoder)) around a type
+//! implementing [`Encode`] or [`Decode`]. Implementing these traits for your own types allows
+//! running other compression formats through the same adaptors.
+
 use crate::util::PartialBuffer;
-use std::io::Result;
+use std::{
+    io::Result,
+    task::{Context, Poll},
+};
 
+mod any;
+mod auto;
 #[cfg(feature = "brotli")]
 mod brotli;
 #[cfg(feature = "bzip2")]
 mod bzip2;
+mod chain;
 #[cfg(feature = "deflate")]
 mod deflate;
-#[cfg(feature = "flate2")]
+#[cfg(any(feature = "deflate", feature = "gzip", feature = "zlib"))]
 mod flate;
 #[cfg(feature = "gzip")]
 mod gzip;
+mod limited;
+#[cfg(feature = "lz4")]
+mod lz4;
+#[cfg(feature = "lzip")]
+mod lzip;
 #[cfg(feature = "lzma")]
 mod lzma;
+#[cfg(feature = "lzma2")]
+mod lzma2;
+#[cfg(feature = "lzma-sys")]
+pub(crate) mod lzma_raw;
 #[cfg(feature = "xz")]
 mod xz;
-#[cfg(feature = "xz2")]
+#[cfg(any(feature = "lzma", feature = "xz"))]
 mod xz2;
 #[cfg(feature = "zlib")]
 mod zlib;
 #[cfg(feature = "zstd")]
 mod zstd;
 
+pub(crate) use self::any::{AnyDecoder, AnyEncoder};
+pub(crate) use self::auto::AutoDecoder;
 #[cfg(feature = "brotli")]
 pub(crate) use self::brotli::{BrotliDecoder, BrotliEncoder};
 #[cfg(feature = "bzip2")]
 pub(crate) use self::bzip2::{BzDecoder, BzEncoder};
+pub(crate) use self::chain::ChainDecoder;
 #[cfg(feature = "deflate")]
 pub(crate) use self::deflate::{DeflateDecoder, DeflateEncoder};
-#[cfg(feature = "flate2")]
+#[cfg(any(feature = "deflate", feature = "gzip", feature = "zlib"))]
 pub(crate) use self::flate::{FlateDecoder, FlateEncoder};
 #[cfg(feature = "gzip")]
-pub(crate) use self::gzip::{GzipDecoder, GzipEncoder};
+pub(crate) use self::gzip::{
+    GzipDecoder, GzipEncoder, ParallelGzipEncoder, PARALLEL_GZIP_BLOCK_SIZE,
+};
+pub(crate) use self::limited::Limited;
+#[cfg(feature = "lz4")]
+pub(crate) use self::lz4::{Lz4Decoder, Lz4Encoder};
+#[cfg(feature = "lzip")]
+pub(crate) use self::lzip::{LzipDecoder, LzipEncoder};
 #[cfg(feature = "lzma")]
 pub(crate) use self::lzma::{LzmaDecoder, LzmaEncoder};
+#[cfg(feature = "lzma2")]
+pub(crate) use self::lzma2::{Lzma2Decoder, Lzma2Encoder};
 #[cfg(feature = "xz")]
 pub(crate) use self::xz::{XzDecoder, XzEncoder};
-#[cfg(feature = "xz2")]
+#[cfg(any(feature = "lzma", feature = "xz"))]
 pub(crate) use self::xz2::{Xz2Decoder, Xz2Encoder, Xz2FileFormat};
 #[cfg(feature = "zlib")]
 pub(crate) use self::zlib::{ZlibDecoder, ZlibEncoder};
 #[cfg(feature = "zstd")]
 pub(crate) use self::zstd::{ZstdDecoder, ZstdEncoder};
 
+/// A compression algorithm that can be driven incrementally by the IO adaptors.
+///
+/// The adaptors call these methods repeatedly with whatever input and output space is currently
+/// available, implementations should consume as much input and produce as much output as they can
+/// and advance the buffers accordingly. Returning without making progress is allowed, the adaptors
+/// will call again once more input or output space is available.
 pub trait Encode {
+    /// Compresses data from `input` into `output`, advancing each by the amount consumed or
+    /// produced.
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -59,12 +102,29 @@ pub trait Encode {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool>;
+
+    /// Called by the adaptors after each of the methods above, returns `Poll::Pending` if that
+    /// call stopped early because it is waiting on work happening elsewhere, such as on other
+    /// threads, after arranging for the task to be woken once the work completes.
+    ///
+    /// The default implementation is for encoders which do all of their work when called, and so
+    /// never wait.
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let _ = cx;
+        Poll::Ready(Ok(()))
+    }
 }
 
+/// A decompression algorithm that can be driven incrementally by the IO adaptors.
+///
+/// See [`Encode`] for how the adaptors drive implementations of this trait.
 pub trait Decode {
     /// Reinitializes this decoder ready to decode a new member/frame of data.
     fn reinit(&mut self) -> Result<()>;
 
+    /// Decompresses data from `input` into `output`, advancing each by the amount consumed or
+    /// produced.
+    ///
     /// Returns whether the end of the stream has been read
     fn decode(
         &mut self,
@@ -81,4 +141,44 @@ pub trait Decode {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool>;
+
+    /// Checks whether `input`, the data following the end of a member/frame, is the start of
+    /// another member/frame, returns `None` if more input is needed to tell.
+    ///
+    /// This lets the adaptors stop before trailing data instead of failing to decode it, the
+    /// default implementation assumes any data is another member/frame, which is correct for
+    /// formats without a recognizable signature.
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        let _ = input;
+        Some(true)
+    }
+}
+
+/// Compares the start of `input` against the fixed `signature` of a format, returns `None` if
+/// `input` is too short to tell.
+pub(crate) fn match_signature(signature: &[u8], input: &[u8]) -> Option<bool> {
+    if input.len() < signature.len() && signature.starts_with(input) {
+        None
+    } else {
+        Some(input.starts_with(signature))
+    }
+}
+
+/// Checks for the start of a zstd or lz4 frame with the format's `magic` number, or of a
+/// skippable frame which both formats share and whose first byte may have any low 4 bits.
+pub(crate) fn match_frame_signature(magic: [u8; 4], input: &[u8]) -> Option<bool> {
+    let skippable = [
+        0x50 | input.first().map_or(0, |byte| byte & 0x0f),
+        0x2a,
+        0x4d,
+        0x18,
+    ];
+    match (
+        match_signature(&magic, input),
+        match_signature(&skippable, input),
+    ) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (None, _) | (_, None) => None,
+        (Some(false), Some(false)) => Some(false),
+    }
 }
@@ -15,6 +15,10 @@ impl XzDecoder {
             skip_padding: None,
         }
     }
+
+    pub fn max_memory_usage(&mut self, limit: Option<u64>) {
+        self.inner.max_memory_usage(limit);
+    }
 }
 
 impl Decode for XzDecoder {
@@ -71,4 +75,15 @@ impl Decode for XzDecoder {
         }
         self.inner.finish(output)
     }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        // Streams may be separated by padding made of null bytes
+        match input.iter().position(|&byte| byte != 0) {
+            Some(start) => crate::codec::match_signature(
+                &[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00],
+                &input[start..],
+            ),
+            None => None,
+        }
+    }
 }
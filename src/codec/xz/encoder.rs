@@ -1,6 +1,9 @@
 use crate::{codec::Encode, util::PartialBuffer};
 
-use std::io::Result;
+use std::{
+    io::Result,
+    task::{Context, Poll},
+};
 
 #[derive(Debug)]
 pub struct XzEncoder {
@@ -13,6 +16,12 @@ impl XzEncoder {
             inner: crate::codec::Xz2Encoder::new(crate::codec::Xz2FileFormat::Xz, level),
         }
     }
+
+    pub fn with_params(level: u32, params: crate::XzParams) -> Result<Self> {
+        Ok(Self {
+            inner: crate::codec::Xz2Encoder::with_params(level, params)?,
+        })
+    }
 }
 
 impl Encode for XzEncoder {
@@ -37,4 +46,8 @@ impl Encode for XzEncoder {
     ) -> Result<bool> {
         self.inner.finish(output)
     }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_wait(cx)
+    }
 }
@@ -1,11 +1,20 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{
+    codec::{
+        lzma_raw::{check, Stream, FINISH, RUN},
+        Decode,
+    },
+    error::LimitExceeded,
+    unshared::Unshared,
+    util::PartialBuffer,
+};
 
+use lzma_sys::{lzma_action, LZMA_BUF_ERROR, LZMA_GET_CHECK, LZMA_MEMLIMIT_ERROR};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::io::Result;
-use xz2::stream::{Action, Status, Stream};
 
 pub struct Xz2Decoder {
-    stream: Stream,
+    stream: Unshared<Stream>,
+    memlimit: Option<u64>,
 }
 
 impl Debug for Xz2Decoder {
@@ -16,43 +25,72 @@ impl Debug for Xz2Decoder {
 
 impl Xz2Decoder {
     pub fn new() -> Self {
+        Self::with_memlimit(None)
+    }
+
+    fn with_memlimit(memlimit: Option<u64>) -> Self {
         Self {
-            stream: Stream::new_auto_decoder(u64::max_value(), 0).unwrap(),
+            stream: Unshared::new(Stream::auto_decoder(memlimit.unwrap_or(u64::MAX)).unwrap()),
+            memlimit,
         }
     }
-}
 
-impl Decode for Xz2Decoder {
-    fn reinit(&mut self) -> Result<()> {
-        *self = Self::new();
-        Ok(())
+    pub fn max_memory_usage(&mut self, limit: Option<u64>) {
+        // liblzma keeps the old limit if the stream already needs more than the new one, `process`
+        // reports that instead
+        let _ = self
+            .stream
+            .get_mut()
+            .set_memlimit(limit.unwrap_or(u64::MAX));
+        self.memlimit = limit;
     }
 
-    fn decode(
+    fn limit_exceeded(&mut self) -> std::io::Error {
+        LimitExceeded::MemoryUsage {
+            limit: self.memlimit.unwrap_or(u64::MAX),
+            required: self.stream.get_mut().memusage(),
+        }
+        .into()
+    }
+
+    fn process(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        action: lzma_action,
     ) -> Result<bool> {
-        let previous_in = self.stream.total_in() as usize;
-        let previous_out = self.stream.total_out() as usize;
-
-        let status = self
-            .stream
-            .process(input.unwritten(), output.unwritten_mut(), Action::Run)?;
-
-        input.advance(self.stream.total_in() as usize - previous_in);
-        output.advance(self.stream.total_out() as usize - previous_out);
+        if let Some(limit) = self.memlimit {
+            if self.stream.get_mut().memusage() > limit {
+                return Err(self.limit_exceeded());
+            }
+        }
 
-        match status {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
+        match self.stream.get_mut().code(input, output, action) {
+            LZMA_MEMLIMIT_ERROR => Err(self.limit_exceeded()),
+            LZMA_BUF_ERROR => Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
                 "More memory needed",
             )),
+            // Only reported when requested with `LZMA_TELL_ANY_CHECK`, decoding just continues
+            LZMA_GET_CHECK => Ok(false),
+            ret => check(ret),
         }
     }
+}
+
+impl Decode for Xz2Decoder {
+    fn reinit(&mut self) -> Result<()> {
+        *self = Self::with_memlimit(self.memlimit);
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.process(input, output, RUN)
+    }
 
     fn flush(
         &mut self,
@@ -66,22 +104,6 @@ impl Decode for Xz2Decoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let previous_out = self.stream.total_out() as usize;
-
-        let status = self
-            .stream
-            .process(&[], output.unwritten_mut(), Action::Finish)?;
-
-        output.advance(self.stream.total_out() as usize - previous_out);
-
-        match status {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "More memory needed",
-            )),
-        }
+        self.process(&mut PartialBuffer::new(&[][..]), output, FINISH)
     }
 }
@@ -1,12 +1,25 @@
 use crate::codec::Xz2FileFormat;
-use crate::{codec::Encode, util::PartialBuffer};
-
+use crate::{
+    codec::{
+        lzma_raw::{self, check, Stream},
+        Encode,
+    },
+    unshared::Unshared,
+    util::PartialBuffer,
+};
+
+use lzma_sys::{lzma_action, lzma_alone_encoder, lzma_easy_encoder, LZMA_CHECK_CRC64};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::io::Result;
-use xz2::stream::{Action, Check, LzmaOptions, Status, Stream};
+use std::task::{Context, Poll};
 
 pub struct Xz2Encoder {
-    stream: Stream,
+    stream: Unshared<Stream>,
+    /// The multithreaded encoder and filters don't support `SyncFlush`.
+    flush: lzma_action,
+    /// Whether the last call made no progress with output space left, which only happens when
+    /// the multithreaded encoder timed out waiting on its workers.
+    waiting: bool,
 }
 
 impl Debug for Xz2Encoder {
@@ -18,13 +31,62 @@ impl Debug for Xz2Encoder {
 impl Xz2Encoder {
     pub fn new(format: Xz2FileFormat, level: u32) -> Self {
         let stream = match format {
-            Xz2FileFormat::Xz => Stream::new_easy_encoder(level, Check::Crc64).unwrap(),
+            Xz2FileFormat::Xz => {
+                Stream::new(|stream| unsafe { lzma_easy_encoder(stream, level, LZMA_CHECK_CRC64) })
+            }
             Xz2FileFormat::Lzma => {
-                Stream::new_lzma_encoder(&LzmaOptions::new_preset(level).unwrap()).unwrap()
+                let options = lzma_raw::preset_options(level).unwrap();
+                Stream::new(|stream| unsafe { lzma_alone_encoder(stream, &options) })
             }
         };
 
-        Self { stream }
+        Self {
+            stream: Unshared::new(stream.unwrap()),
+            flush: lzma_raw::SYNC_FLUSH,
+            waiting: false,
+        }
+    }
+
+    #[cfg(feature = "xz")]
+    pub fn with_params(level: u32, params: crate::XzParams) -> Result<Self> {
+        let flush = if params.supports_sync_flush() {
+            lzma_raw::SYNC_FLUSH
+        } else {
+            lzma_raw::FULL_FLUSH
+        };
+
+        Ok(Self {
+            stream: Unshared::new(params.into_encoder(level)?),
+            flush,
+            waiting: false,
+        })
+    }
+
+    fn code(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        action: lzma_action,
+    ) -> Result<bool> {
+        let (prior_in, prior_out) = (input.written().len(), output.written().len());
+
+        let done = check(self.stream.get_mut().code(input, output, action))?;
+
+        self.waiting = !done
+            && input.written().len() == prior_in
+            && output.written().len() == prior_out
+            && !output.unwritten().is_empty();
+
+        Ok(done)
+    }
+
+    fn process(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        action: lzma_action,
+    ) -> Result<bool> {
+        let mut input = PartialBuffer::new(&[][..]);
+        self.code(&mut input, output, action)
     }
 }
 
@@ -34,69 +96,33 @@ impl Encode for Xz2Encoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()> {
-        let previous_in = self.stream.total_in() as usize;
-        let previous_out = self.stream.total_out() as usize;
-
-        let status = self
-            .stream
-            .process(input.unwritten(), output.unwritten_mut(), Action::Run)?;
-
-        input.advance(self.stream.total_in() as usize - previous_in);
-        output.advance(self.stream.total_out() as usize - previous_out);
-
-        match status {
-            Status::Ok | Status::StreamEnd => Ok(()),
-            Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
-        }
+        self.code(input, output, lzma_raw::RUN)?;
+        Ok(())
     }
 
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let previous_out = self.stream.total_out() as usize;
-
-        let status = self
-            .stream
-            .process(&[], output.unwritten_mut(), Action::SyncFlush)?;
-
-        output.advance(self.stream.total_out() as usize - previous_out);
-
-        match status {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
-        }
+        self.process(output, self.flush)
     }
 
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let previous_out = self.stream.total_out() as usize;
-
-        let status = self
-            .stream
-            .process(&[], output.unwritten_mut(), Action::Finish)?;
-
-        output.advance(self.stream.total_out() as usize - previous_out);
-
-        match status {
-            Status::Ok => Ok(false),
-            Status::StreamEnd => Ok(true),
-            Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
+        self.process(output, lzma_raw::FINISH)
+    }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if !self.waiting {
+            return Poll::Ready(Ok(()));
         }
+
+        // liblzma can't notify when its workers progress, so yield and check again next poll,
+        // each check waits on them for at most the short timeout the encoder was created with
+        self.waiting = false;
+        cx.waker().wake_by_ref();
+        Poll::Pending
     }
 }
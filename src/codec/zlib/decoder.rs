@@ -1,22 +1,161 @@
-use crate::util::PartialBuffer;
-use std::io::Result;
+use crate::{codec::Decode, util::PartialBuffer, DictionaryRequired};
+use std::io::{Error, ErrorKind, Result};
+
+use adler32::RollingAdler32;
 
+#[derive(Debug)]
+enum State {
+    Header(PartialBuffer<Vec<u8>>),
+    DictionaryId(PartialBuffer<Vec<u8>>),
+    Decoding,
+    Footer(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
+/// The header and footer are handled here rather than by zlib, so that the dictionary a stream
+/// needs can be checked against the one given and reported in `DictionaryRequired`.
 #[derive(Debug)]
 pub struct ZlibDecoder {
     inner: crate::codec::FlateDecoder,
+    dictionary: Option<Vec<u8>>,
+    /// The largest window streams may use.
+    window_bits: u32,
+    adler: u32,
+    state: State,
+}
+
+/// zlib has no fixed signature, but the first byte must specify deflate with at most a 32 KiB window
+/// and the first two bytes together must be a multiple of 31.
+pub(crate) fn match_header(input: &[u8]) -> Option<bool> {
+    match *input {
+        [] => None,
+        [cmf, ..] if cmf & 0x0f != 8 || cmf >> 4 > 7 => Some(false),
+        [_] => None,
+        [cmf, flg, ..] => Some((u16::from(cmf) << 8 | u16::from(flg)) % 31 == 0),
+    }
 }
 
 impl ZlibDecoder {
     pub(crate) fn new() -> Self {
         Self {
-            inner: crate::codec::FlateDecoder::new(true),
+            inner: crate::codec::FlateDecoder::new(false),
+            dictionary: None,
+            window_bits: 15,
+            adler: RollingAdler32::new().hash(),
+            state: State::Header(vec![0; 2].into()),
+        }
+    }
+
+    pub(crate) fn with_dictionary(dictionary: &[u8]) -> Self {
+        Self {
+            dictionary: Some(dictionary.to_vec()),
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn window_bits(&mut self, bits: u32) {
+        self.window_bits = bits.clamp(8, 15);
+        self.inner.window_bits(bits);
+    }
+
+    fn set_dictionary(&mut self, id: u32) -> Result<()> {
+        let given_id = self
+            .dictionary
+            .as_ref()
+            .map(|dictionary| RollingAdler32::from_buffer(dictionary).hash());
+
+        match &self.dictionary {
+            Some(dictionary) if given_id == Some(id) => self.inner.set_dictionary(dictionary),
+            _ => Err(DictionaryRequired { id, given_id }.into()),
+        }
+    }
+
+    fn process<I: AsRef<[u8]>, O: AsRef<[u8]> + AsMut<[u8]>>(
+        &mut self,
+        input: &mut PartialBuffer<I>,
+        output: &mut PartialBuffer<O>,
+        inner: impl Fn(&mut Self, &mut PartialBuffer<I>, &mut PartialBuffer<O>) -> Result<bool>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Header(header) => {
+                    header.copy_unwritten_from(input);
+
+                    if header.unwritten().is_empty() {
+                        let header = header.written();
+                        if match_header(header) != Some(true) {
+                            return Err(Error::new(ErrorKind::InvalidData, "Invalid zlib header"));
+                        }
+                        if u32::from(header[0] >> 4) + 8 > self.window_bits {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                "zlib window is larger than allowed",
+                            ));
+                        }
+
+                        self.state = if header[1] & 0x20 != 0 {
+                            State::DictionaryId(vec![0; 4].into())
+                        } else {
+                            State::Decoding
+                        };
+                    }
+                }
+
+                State::DictionaryId(id) => {
+                    id.copy_unwritten_from(input);
+
+                    if id.unwritten().is_empty() {
+                        let mut bytes = [0; 4];
+                        bytes.copy_from_slice(id.written());
+                        self.set_dictionary(u32::from_be_bytes(bytes))?;
+                        self.state = State::Decoding;
+                    }
+                }
+
+                State::Decoding => {
+                    let prior = output.written().len();
+                    let done = inner(self, input, output)?;
+                    let mut adler = RollingAdler32::from_value(self.adler);
+                    adler.update_buffer(&output.written()[prior..]);
+                    self.adler = adler.hash();
+                    if done {
+                        self.state = State::Footer(vec![0; 4].into())
+                    }
+                }
+
+                State::Footer(footer) => {
+                    footer.copy_unwritten_from(input);
+
+                    if footer.unwritten().is_empty() {
+                        if footer.written() != self.adler.to_be_bytes() {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                "Adler-32 computed does not match",
+                            ));
+                        }
+                        self.state = State::Done
+                    }
+                }
+
+                State::Done => {}
+            };
+
+            if let State::Done = self.state {
+                return Ok(true);
+            }
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(false);
+            }
         }
     }
 }
 
-impl crate::codec::Decode for ZlibDecoder {
+impl Decode for ZlibDecoder {
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()?;
+        self.adler = RollingAdler32::new().hash();
+        self.state = State::Header(vec![0; 2].into());
         Ok(())
     }
 
@@ -25,20 +164,55 @@ impl crate::codec::Decode for ZlibDecoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.decode(input, output)
+        self.process(input, output, |this, input, output| {
+            this.inner.decode(input, output)
+        })
     }
 
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.flush(output)
+        loop {
+            match self.state {
+                State::Header(_) | State::DictionaryId(_) | State::Footer(_) | State::Done => {
+                    return Ok(true)
+                }
+
+                State::Decoding => {
+                    let prior = output.written().len();
+                    let done = self.inner.flush(output)?;
+                    let mut adler = RollingAdler32::from_value(self.adler);
+                    adler.update_buffer(&output.written()[prior..]);
+                    self.adler = adler.hash();
+                    if done {
+                        return Ok(true);
+                    }
+                }
+            };
+
+            if output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
     }
 
     fn finish(
         &mut self,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.finish(output)
+        // Because of the footer we have to have already flushed all the data out before we get here
+        if let State::Done = self.state {
+            Ok(true)
+        } else {
+            Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "unexpected end of file",
+            ))
+        }
+    }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        match_header(input)
     }
 }
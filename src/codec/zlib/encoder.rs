@@ -14,6 +14,19 @@ impl ZlibEncoder {
             inner: crate::codec::FlateEncoder::new(level, true),
         }
     }
+
+    /// zlib records the dictionary's ID in the header itself.
+    pub(crate) fn with_dictionary(level: Compression, dictionary: &[u8]) -> Result<Self> {
+        let mut inner = crate::codec::FlateEncoder::new(level, true);
+        inner.set_dictionary(dictionary)?;
+        Ok(Self { inner })
+    }
+
+    pub(crate) fn with_params(level: Compression, params: crate::FlateParams) -> Self {
+        Self {
+            inner: crate::codec::FlateEncoder::with_params(level, true, params),
+        }
+    }
 }
 
 impl Encode for ZlibEncoder {
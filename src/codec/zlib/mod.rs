@@ -1,4 +1,7 @@
 mod decoder;
 mod encoder;
 
-pub(crate) use self::{decoder::ZlibDecoder, encoder::ZlibEncoder};
+pub(crate) use self::{
+    decoder::{match_header, ZlibDecoder},
+    encoder::ZlibEncoder,
+};
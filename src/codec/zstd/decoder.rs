@@ -1,19 +1,42 @@
 use std::io::Result;
 
-use crate::{codec::Decode, unshared::Unshared, util::PartialBuffer};
+use crate::{codec::Decode, unshared::Unshared, util::PartialBuffer, ZstdDecoderDictionary};
 use libzstd::stream::raw::{Decoder, Operation};
 
 #[derive(Debug)]
 pub struct ZstdDecoder {
     decoder: Unshared<Decoder>,
+    // Referenced by `decoder`, so must be dropped after it.
+    _dictionary: Option<ZstdDecoderDictionary>,
 }
 
 impl ZstdDecoder {
     pub(crate) fn new() -> Self {
         Self {
             decoder: Unshared::new(Decoder::new().unwrap()),
+            _dictionary: None,
         }
     }
+
+    pub(crate) fn window_log_max(&mut self, log: u32) -> Result<()> {
+        self.decoder
+            .get_mut()
+            .set_parameter(zstd_safe::DParameter::WindowLogMax(log))
+    }
+
+    pub(crate) fn with_dictionary(dictionary: &[u8]) -> Result<Self> {
+        Ok(Self {
+            decoder: Unshared::new(Decoder::with_dictionary(dictionary)?),
+            _dictionary: None,
+        })
+    }
+
+    pub(crate) fn with_prepared_dictionary(dictionary: &ZstdDecoderDictionary) -> Result<Self> {
+        Ok(Self {
+            decoder: Unshared::new(Decoder::with_prepared_dictionary(dictionary.get_ref())?),
+            _dictionary: Some(dictionary.clone()),
+        })
+    }
 }
 
 impl Decode for ZstdDecoder {
@@ -57,4 +80,8 @@ impl Decode for ZstdDecoder {
         output.advance(len);
         Ok(bytes_left == 0)
     }
+
+    fn is_member_start(&self, input: &[u8]) -> Option<bool> {
+        crate::codec::match_frame_signature([0x28, 0xb5, 0x2f, 0xfd], input)
+    }
 }
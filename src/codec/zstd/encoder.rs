@@ -1,18 +1,70 @@
-use crate::{codec::Encode, unshared::Unshared, util::PartialBuffer};
+use crate::{
+    codec::Encode, unshared::Unshared, util::PartialBuffer, ZstdEncoderDictionary, ZstdParams,
+};
 use libzstd::stream::raw::{Encoder, Operation};
-use std::io::Result;
+use std::{
+    io::Result,
+    task::{Context, Poll},
+};
+
+#[derive(Debug)]
+enum Inner {
+    Direct(Unshared<Encoder>),
+    /// Compressing with worker threads, which is driven from another thread as libzstd blocks
+    /// while waiting on them.
+    #[cfg(feature = "zstdmt")]
+    Threaded(super::threaded::ThreadedEncoder),
+}
 
 #[derive(Debug)]
 pub struct ZstdEncoder {
-    encoder: Unshared<Encoder>,
+    inner: Inner,
+    // Referenced by `inner`, so must be dropped after it.
+    _dictionary: Option<ZstdEncoderDictionary>,
 }
 
 impl ZstdEncoder {
     pub(crate) fn new(level: i32) -> Self {
         Self {
-            encoder: Unshared::new(Encoder::new(level).unwrap()),
+            inner: Inner::Direct(Unshared::new(Encoder::new(level).unwrap())),
+            _dictionary: None,
         }
     }
+
+    pub(crate) fn with_params(level: i32, params: ZstdParams) -> Result<Self> {
+        let mut encoder = Encoder::new(level)?;
+        params.apply(&mut encoder)?;
+
+        #[cfg(feature = "zstdmt")]
+        let inner = if params.uses_workers() {
+            Inner::Threaded(super::threaded::ThreadedEncoder::new(encoder)?)
+        } else {
+            Inner::Direct(Unshared::new(encoder))
+        };
+        #[cfg(not(feature = "zstdmt"))]
+        let inner = Inner::Direct(Unshared::new(encoder));
+
+        Ok(Self {
+            inner,
+            _dictionary: None,
+        })
+    }
+
+    pub(crate) fn with_dictionary(level: i32, dictionary: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: Inner::Direct(Unshared::new(Encoder::with_dictionary(level, dictionary)?)),
+            _dictionary: None,
+        })
+    }
+
+    pub(crate) fn with_prepared_dictionary(dictionary: &ZstdEncoderDictionary) -> Result<Self> {
+        Ok(Self {
+            inner: Inner::Direct(Unshared::new(Encoder::with_prepared_dictionary(
+                dictionary.get_ref(),
+            )?)),
+            _dictionary: Some(dictionary.clone()),
+        })
+    }
 }
 
 impl Encode for ZstdEncoder {
@@ -21,10 +73,13 @@ impl Encode for ZstdEncoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()> {
-        let status = self
-            .encoder
-            .get_mut()
-            .run_on_buffers(input.unwritten(), output.unwritten_mut())?;
+        let encoder = match &mut self.inner {
+            Inner::Direct(encoder) => encoder.get_mut(),
+            #[cfg(feature = "zstdmt")]
+            Inner::Threaded(encoder) => return encoder.encode(input, output),
+        };
+
+        let status = encoder.run_on_buffers(input.unwritten(), output.unwritten_mut())?;
         input.advance(status.bytes_read);
         output.advance(status.bytes_written);
         Ok(())
@@ -34,8 +89,14 @@ impl Encode for ZstdEncoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
+        let encoder = match &mut self.inner {
+            Inner::Direct(encoder) => encoder.get_mut(),
+            #[cfg(feature = "zstdmt")]
+            Inner::Threaded(encoder) => return encoder.flush(output),
+        };
+
         let mut out_buf = zstd_safe::OutBuffer::around(output.unwritten_mut());
-        let bytes_left = self.encoder.get_mut().flush(&mut out_buf)?;
+        let bytes_left = encoder.flush(&mut out_buf)?;
         let len = out_buf.as_slice().len();
         output.advance(len);
         Ok(bytes_left == 0)
@@ -45,10 +106,27 @@ impl Encode for ZstdEncoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
+        let encoder = match &mut self.inner {
+            Inner::Direct(encoder) => encoder.get_mut(),
+            #[cfg(feature = "zstdmt")]
+            Inner::Threaded(encoder) => return encoder.finish(output),
+        };
+
         let mut out_buf = zstd_safe::OutBuffer::around(output.unwritten_mut());
-        let bytes_left = self.encoder.get_mut().finish(&mut out_buf, true)?;
+        let bytes_left = encoder.finish(&mut out_buf, true)?;
         let len = out_buf.as_slice().len();
         output.advance(len);
         Ok(bytes_left == 0)
     }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.inner {
+            Inner::Direct(_) => {
+                let _ = cx;
+                Poll::Ready(Ok(()))
+            }
+            #[cfg(feature = "zstdmt")]
+            Inner::Threaded(encoder) => encoder.poll_wait(cx),
+        }
+    }
 }
@@ -1,4 +1,6 @@
 mod decoder;
 mod encoder;
+#[cfg(feature = "zstdmt")]
+mod threaded;
 
 pub(crate) use self::{decoder::ZstdDecoder, encoder::ZstdEncoder};
@@ -0,0 +1,247 @@
+use crate::{codec::Encode, unshared::Unshared, util::PartialBuffer};
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+    sync::{
+        mpsc::{self, Receiver, Sender, SyncSender, TryRecvError},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+use libzstd::stream::raw::{Encoder, Operation};
+
+enum Command {
+    Encode(Vec<u8>),
+    Flush,
+    Finish,
+}
+
+impl Command {
+    /// Runs the command to completion, returning all of the output it produced.
+    fn run(self, encoder: &mut Encoder, buffer: &mut [u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+
+        match self {
+            Command::Encode(input) => {
+                let mut input = &input[..];
+                while !input.is_empty() {
+                    let status = encoder.run_on_buffers(input, buffer)?;
+                    input = &input[status.bytes_read..];
+                    output.extend_from_slice(&buffer[..status.bytes_written]);
+                }
+            }
+
+            Command::Flush | Command::Finish => loop {
+                let finish = matches!(self, Command::Finish);
+                let mut out_buf = zstd_safe::OutBuffer::around(&mut *buffer);
+                let bytes_left = if finish {
+                    encoder.finish(&mut out_buf, true)?
+                } else {
+                    encoder.flush(&mut out_buf)?
+                };
+                output.extend_from_slice(out_buf.as_slice());
+                if bytes_left == 0 {
+                    break;
+                }
+            },
+        }
+
+        Ok(output)
+    }
+}
+
+fn worker(
+    mut encoder: Encoder,
+    commands: Receiver<Command>,
+    results: SyncSender<Result<Vec<u8>>>,
+    waker: Arc<Mutex<Option<Waker>>>,
+) {
+    let mut buffer = vec![0; zstd_safe::cstream_out_size()];
+
+    for command in commands {
+        if results
+            .send(command.run(&mut encoder, &mut buffer))
+            .is_err()
+        {
+            return;
+        }
+        let waker = waker.lock().unwrap().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Drives a zstd encoder using worker threads from a thread of its own.
+///
+/// libzstd blocks while waiting on its workers, both when there is no room to hand them more
+/// input and when flushing or finishing, so every call into it is made on this encoder's thread.
+/// Only one command is in flight at a time, while it runs the encoder reports that it's waiting
+/// through `poll_wait` and the thread wakes the task once the command completes.
+pub(crate) struct ThreadedEncoder {
+    commands: Unshared<Sender<Command>>,
+    results: Unshared<Receiver<Result<Vec<u8>>>>,
+    waker: Arc<Mutex<Option<Waker>>>,
+    /// Whether a command has been sent and its result not yet received.
+    busy: bool,
+    /// Whether the last call stopped because the command in flight is not complete.
+    waiting: bool,
+    /// The output of the last completed command while it's being written.
+    output: PartialBuffer<Vec<u8>>,
+    /// Set once a flush or finish command has been sent, until its output is written.
+    flushing: bool,
+    finished: bool,
+}
+
+impl ThreadedEncoder {
+    pub(crate) fn new(encoder: Encoder) -> Result<Self> {
+        let (commands, receiver) = mpsc::channel();
+        let (sender, results) = mpsc::sync_channel(1);
+        let waker = Arc::new(Mutex::new(None));
+
+        let worker_waker = waker.clone();
+        thread::Builder::new()
+            .name("async-compression-zstd".into())
+            .spawn(move || worker(encoder, receiver, sender, worker_waker))?;
+
+        Ok(Self {
+            commands: Unshared::new(commands),
+            results: Unshared::new(results),
+            waker,
+            busy: false,
+            waiting: false,
+            output: Vec::new().into(),
+            flushing: false,
+            finished: false,
+        })
+    }
+
+    fn send(&mut self, command: Command) -> Result<()> {
+        self.commands
+            .get_mut()
+            .send(command)
+            .map_err(|_| Error::new(ErrorKind::Other, "zstd encoder thread exited"))?;
+        self.busy = true;
+        Ok(())
+    }
+
+    /// Takes the output of the command in flight if it's complete, returns whether no command is
+    /// in flight any more.
+    fn receive(&mut self) -> Result<bool> {
+        if !self.busy {
+            return Ok(true);
+        }
+
+        let result = match self.results.get_mut().try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return Ok(false),
+            Err(TryRecvError::Disconnected) => {
+                return Err(Error::new(ErrorKind::Other, "zstd encoder thread panicked"))
+            }
+        };
+        self.busy = false;
+        self.output = result?.into();
+
+        Ok(true)
+    }
+
+    /// Writes out as much completed output as possible, returns whether everything sent to the
+    /// thread so far has been written. If the command in flight isn't complete yet the encoder is
+    /// left waiting on it.
+    fn drain(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.waiting = false;
+
+        if !self.receive()? {
+            self.waiting = true;
+            return Ok(false);
+        }
+
+        output.copy_unwritten_from(&mut self.output);
+        Ok(self.output.unwritten().is_empty())
+    }
+}
+
+impl Encode for ThreadedEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        if !self.drain(output)? || input.unwritten().is_empty() {
+            return Ok(());
+        }
+
+        let len = input.unwritten().len();
+        self.send(Command::Encode(input.unwritten().to_vec()))?;
+        input.advance(len);
+
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.drain(output)? {
+            return Ok(false);
+        }
+
+        if self.flushing {
+            self.flushing = false;
+            return Ok(true);
+        }
+
+        self.send(Command::Flush)?;
+        self.flushing = true;
+        Ok(false)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.drain(output)? {
+            return Ok(false);
+        }
+
+        if self.finished {
+            return Ok(true);
+        }
+
+        self.send(Command::Finish)?;
+        self.finished = true;
+        Ok(false)
+    }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if !self.waiting {
+            return Poll::Ready(Ok(()));
+        }
+
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        // The command may have completed before the waker was registered, in which case the
+        // thread found nothing to wake
+        if self.receive()? {
+            self.waiting = false;
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for ThreadedEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadedEncoder")
+            .field("busy", &self.busy)
+            .field("flushing", &self.flushing)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
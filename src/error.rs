@@ -0,0 +1,138 @@
+//! Error types that may be found wrapped inside the [`std::io::Error`]s returned by this crate.
+
+use core::fmt;
+use std::io;
+
+/// A decoder stopped because its output or memory usage exceeded one of the limits configured on
+/// it, e.g. with [`max_output_size`](crate::futures::bufread::Decoder::max_output_size).
+///
+/// This is returned wrapped in an [`io::Error`] of kind [`Other`](io::ErrorKind::Other), and can
+/// be retrieved from it with [`io::Error::get_ref`] and
+/// [`downcast_ref`](std::error::Error#method.downcast_ref).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// The total decompressed output would have been larger than this many bytes.
+    OutputSize(u64),
+    /// The decompressed output would have been larger than this multiple of the compressed
+    /// input read so far.
+    Ratio(u64),
+    /// Decoding the data needs more memory than the limit set with the xz and lzma decoders'
+    /// `max_memory_usage`, mostly for the dictionary whose size is chosen by the encoder.
+    MemoryUsage {
+        /// The configured limit in bytes.
+        limit: u64,
+        /// How many bytes the decoder needs.
+        required: u64,
+    },
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutputSize(limit) => {
+                write!(
+                    f,
+                    "decompressed output exceeds the limit of {} bytes",
+                    limit
+                )
+            }
+            Self::Ratio(limit) => write!(f, "decompression ratio exceeds the limit of {}", limit),
+            Self::MemoryUsage { limit, required } => write!(
+                f,
+                "decompression needs {} bytes of memory, exceeding the limit of {} bytes",
+                required, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl From<LimitExceeded> for io::Error {
+    fn from(err: LimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::Other, err)
+    }
+}
+
+/// A zlib stream was compressed with a preset dictionary and the decoder was given none, or a
+/// different one. Dictionaries are identified by their Adler-32 checksum.
+///
+/// This is returned wrapped in an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData)
+/// by the zlib decoders, and can be retrieved from it with [`io::Error::get_ref`] and
+/// [`downcast_ref`](std::error::Error#method.downcast_ref).
+#[cfg(feature = "zlib")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zlib")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DictionaryRequired {
+    pub(crate) id: u32,
+    pub(crate) given_id: Option<u32>,
+}
+
+#[cfg(feature = "zlib")]
+impl DictionaryRequired {
+    /// Returns the Adler-32 checksum of the dictionary the stream was compressed with.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the Adler-32 checksum of the dictionary the decoder was given, if any.
+    pub fn given_id(&self) -> Option<u32> {
+        self.given_id
+    }
+}
+
+#[cfg(feature = "zlib")]
+impl fmt::Display for DictionaryRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.given_id {
+            None => write!(f, "preset dictionary {:#010x} is required", self.id),
+            Some(given_id) => write!(
+                f,
+                "preset dictionary {:#010x} is required, but {:#010x} was given",
+                self.id, given_id
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "zlib")]
+impl std::error::Error for DictionaryRequired {}
+
+#[cfg(feature = "zlib")]
+impl From<DictionaryRequired> for io::Error {
+    fn from(err: DictionaryRequired) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// A `Content-Encoding` named an encoding that is not supported, either because it is unknown or
+/// because the feature for its algorithm is not enabled.
+///
+/// This is returned wrapped in an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData)
+/// by [`http::parse_content_encoding`](crate::http::parse_content_encoding).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedEncoding {
+    pub(crate) encoding: String,
+}
+
+impl UnsupportedEncoding {
+    /// Returns the name of the unsupported encoding, as written in the header.
+    pub fn encoding(&self) -> &str {
+        &self.encoding
+    }
+}
+
+impl fmt::Display for UnsupportedEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported content encoding {:?}", self.encoding)
+    }
+}
+
+impl std::error::Error for UnsupportedEncoding {}
+
+impl From<UnsupportedEncoding> for io::Error {
+    fn from(err: UnsupportedEncoding) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
@@ -4,7 +4,10 @@ use core::{
 };
 use std::io::Result;
 
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{
+    codec::{Decode, Limited},
+    util::PartialBuffer,
+};
 use futures_core::ready;
 use futures_io::{AsyncBufRead, AsyncRead};
 use pin_project_lite::pin_project;
@@ -18,46 +21,107 @@ enum State {
 }
 
 pin_project! {
+    /// A generic decoder which reads compressed data from an underlying
+    /// [`AsyncBufRead`](futures_io::AsyncBufRead) and decompresses it with any [`Decode`] implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](futures_io::AsyncRead) interface.
     #[derive(Debug)]
     pub struct Decoder<R, D: Decode> {
         #[pin]
         reader: R,
-        decoder: D,
+        decoder: Limited<D>,
         state: State,
         multiple_members: bool,
+        stop_at_trailing_data: bool,
     }
 }
 
 impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
+    /// Creates a new decoder which will read compressed data from the given stream and decompress
+    /// it with `decoder`.
     pub fn new(reader: R, decoder: D) -> Self {
         Self {
             reader,
-            decoder,
+            decoder: Limited::new(decoder),
             state: State::Decoding,
             multiple_members: false,
+            stop_at_trailing_data: false,
         }
     }
 
+    /// Acquires a reference to the underlying reader that this decoder is wrapping.
     pub fn get_ref(&self) -> &R {
         &self.reader
     }
 
+    /// Acquires a mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.reader
     }
 
+    /// Acquires a pinned mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
         self.project().reader
     }
 
+    /// Consumes this decoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> R {
         self.reader
     }
 
+    pub(crate) fn get_decoder_ref(&self) -> &D {
+        self.decoder.get_ref()
+    }
+
+    pub(crate) fn get_decoder_mut(&mut self) -> &mut D {
+        self.decoder.get_mut()
+    }
+
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either EOF or another compressed
+    /// member/frame to follow it in the stream.
     pub fn multiple_members(&mut self, enabled: bool) {
         self.multiple_members = enabled;
     }
 
+    /// Configure stopping at trailing data when decoding multiple members/frames, if enabled the
+    /// data following the end of each member/frame is checked for the signature of the format and
+    /// the decoder stops cleanly if it doesn't match, leaving the trailing data unread in the
+    /// underlying reader. Otherwise that data is decoded as another member/frame, likely failing
+    /// with an error.
+    ///
+    /// The check only looks at the data currently buffered by the underlying reader, if that is
+    /// shorter than the signature and matches as far as it goes it is taken to be another
+    /// member/frame. Formats without a signature (brotli, deflate, lzma and raw LZMA2) never stop
+    /// early.
+    pub fn stop_at_trailing_data(&mut self, enabled: bool) {
+        self.stop_at_trailing_data = enabled;
+    }
+
+    /// Limits the total number of bytes this decoder will output, exceeding it fails with a
+    /// [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no limit
+    /// by default.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.decoder.max_output_size(limit);
+    }
+
+    /// Limits the ratio between the number of bytes output and compressed bytes read by this
+    /// decoder, exceeding it fails with a [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio)
+    /// error. This is checked continuously while decoding, not just at the end of the stream.
+    /// There is no limit by default.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.decoder.max_ratio(limit);
+    }
+
     fn do_poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -101,7 +165,10 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
 
                 State::Next => {
                     let input = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
-                    if input.is_empty() {
+                    if input.is_empty()
+                        || (*this.stop_at_trailing_data
+                            && this.decoder.is_member_start(input) == Some(false))
+                    {
                         State::Done
                     } else {
                         State::Decoding
@@ -112,6 +179,13 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
             if let State::Done = *this.state {
                 return Poll::Ready(Ok(()));
             }
+            if let State::Next = *this.state {
+                // Don't mix output from separate members, so that any per-member state exposed
+                // by the decoder matches the data last returned.
+                if !output.written().is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+            }
             if output.unwritten().is_empty() {
                 return Poll::Ready(Ok(()));
             }
@@ -17,6 +17,10 @@ enum State {
 }
 
 pin_project! {
+    /// A generic encoder which reads uncompressed data from an underlying
+    /// [`AsyncBufRead`](futures_io::AsyncBufRead) and compresses it with any [`Encode`] implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](futures_io::AsyncRead) interface.
     #[derive(Debug)]
     pub struct Encoder<R, E: Encode> {
         #[pin]
@@ -27,6 +31,8 @@ pin_project! {
 }
 
 impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
+    /// Creates a new encoder which will read uncompressed data from the given stream and compress
+    /// it with `encoder`.
     pub fn new(reader: R, encoder: E) -> Self {
         Self {
             reader,
@@ -35,18 +41,31 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
         }
     }
 
+    /// Acquires a reference to the underlying reader that this encoder is wrapping.
     pub fn get_ref(&self) -> &R {
         &self.reader
     }
 
+    /// Acquires a mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.reader
     }
 
+    /// Acquires a pinned mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
         self.project().reader
     }
 
+    /// Consumes this encoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> R {
         self.reader
     }
@@ -90,6 +109,9 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
             if output.unwritten().is_empty() {
                 return Poll::Ready(Ok(()));
             }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
         }
     }
 }
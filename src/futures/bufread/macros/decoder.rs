@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident { $($constructor:tt)* } $({ $($method:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -15,11 +15,8 @@ macro_rules! decoder {
         impl<R: futures_io::AsyncBufRead> $name<R> {
             /// Creates a new decoder which will read compressed data from the given stream and
             /// emit a uncompressed stream.
-            pub fn new(read: R) -> $name<R> {
-                $name {
-                    inner: crate::futures::bufread::Decoder::new(read, crate::codec::$name::new()),
-                }
-            }
+            ///
+            $($constructor)*
 
             /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
             /// when reaching the end of a compressed member/frame and expect either EOF or another
@@ -28,6 +25,36 @@ macro_rules! decoder {
                 self.inner.multiple_members(enabled);
             }
 
+            /// Configure stopping at trailing data when decoding multiple members/frames, if
+            /// enabled the data following the end of each member/frame is checked for the
+            /// signature of the format and the decoder stops cleanly if it doesn't match, leaving
+            /// the trailing data unread in the underlying reader. Otherwise that data is decoded as
+            /// another member/frame, likely failing with an error.
+            ///
+            /// The check only looks at the data currently buffered by the underlying reader, if
+            /// that is shorter than the signature and matches as far as it goes it is taken to be
+            /// another member/frame. Formats without a signature (brotli, deflate, lzma and raw
+            /// LZMA2) never stop early.
+            pub fn stop_at_trailing_data(&mut self, enabled: bool) {
+                self.inner.stop_at_trailing_data(enabled);
+            }
+
+            /// Limits the total number of bytes this decoder will output, exceeding it fails with
+            /// a [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no
+            /// limit by default.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits the ratio between the number of bytes output and compressed bytes read by
+            /// this decoder, exceeding it fails with a
+            /// [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio) error. This is checked
+            /// continuously while decoding, not just at the end of the stream. There is no limit
+            /// by default.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
             /// Acquires a reference to the underlying reader that this decoder is wrapping.
             pub fn get_ref(&self) -> &R {
                 self.inner.get_ref()
@@ -58,6 +85,8 @@ macro_rules! decoder {
             pub fn into_inner(self) -> R {
                 self.inner.into_inner()
             }
+
+            $($($method)*)*
         }
 
         impl<R: futures_io::AsyncBufRead> futures_io::AsyncRead for $name<R> {
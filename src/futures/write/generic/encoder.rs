@@ -2,7 +2,7 @@ use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use std::io::Result;
+use std::io::{Error, ErrorKind, Result};
 
 use crate::{
     codec::Encode,
@@ -21,6 +21,8 @@ enum State {
 }
 
 pin_project! {
+    /// A generic encoder which takes in uncompressed data, compresses it with any [`Encode`]
+    /// implementation and writes it to an underlying [`AsyncWrite`](futures_io::AsyncWrite).
     #[derive(Debug)]
     pub struct Encoder<W, E: Encode> {
         #[pin]
@@ -31,6 +33,8 @@ pin_project! {
 }
 
 impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
+    /// Creates a new encoder which will take in uncompressed data, compress it with `encoder` and write
+    /// it to the given stream.
     pub fn new(writer: W, encoder: E) -> Self {
         Self {
             writer: BufWriter::new(writer),
@@ -39,22 +43,46 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
         }
     }
 
+    /// Acquires a reference to the underlying writer that this encoder is wrapping.
     pub fn get_ref(&self) -> &W {
         self.writer.get_ref()
     }
 
+    /// Acquires a mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
     pub fn get_mut(&mut self) -> &mut W {
         self.writer.get_mut()
     }
 
+    /// Acquires a pinned mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
         self.project().writer.get_pin_mut()
     }
 
+    /// Consumes this encoder returning the underlying writer.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> W {
         self.writer.into_inner()
     }
 
+    /// Sets the size of the buffer holding output until it is written to the underlying writer.
+    /// The default is currently 8 KB, but may change in the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn buffer_size(&mut self, size: usize) {
+        assert!(size > 0, "buffer size must be non-zero");
+        self.writer.set_capacity(size);
+    }
+
     fn do_poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -72,7 +100,9 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
                     State::Encoding
                 }
 
-                State::Finishing | State::Done => panic!("Write after close"),
+                State::Finishing | State::Done => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Other, "Write after close")))
+                }
             };
 
             let produced = output.written().len();
@@ -81,6 +111,9 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             if input.unwritten().is_empty() {
                 return Poll::Ready(Ok(()));
             }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
         }
     }
 
@@ -94,7 +127,9 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             let done = match this.state {
                 State::Encoding => this.encoder.flush(&mut output)?,
 
-                State::Finishing | State::Done => panic!("Flush after close"),
+                State::Finishing | State::Done => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Other, "Flush after close")))
+                }
             };
 
             let produced = output.written().len();
@@ -103,6 +138,9 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             if done {
                 return Poll::Ready(Ok(()));
             }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
         }
     }
 
@@ -131,6 +169,9 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             if let State::Done = this.state {
                 return Poll::Ready(Ok(()));
             }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
         }
     }
 }
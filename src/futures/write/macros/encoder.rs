@@ -20,6 +20,16 @@ macro_rules! encoder {
                 $($constructor)*
             )*
 
+            /// Sets the size of the buffer holding output until it is written to the underlying
+            /// writer. The default is currently 8 KB, but may change in the future.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `size` is zero.
+            pub fn buffer_size(&mut self, size: usize) {
+                self.inner.buffer_size(size);
+            }
+
             /// Acquires a reference to the underlying writer that this encoder is wrapping.
             pub fn get_ref(&self) -> &$inner {
                 self.inner.get_ref()
@@ -0,0 +1,175 @@
+//! Helpers for the HTTP `Content-Encoding` and `Accept-Encoding` headers.
+//!
+//! A body with a `Content-Encoding` can be decoded with the [`ChainDecoder`](?search=ChainDecoder)
+//! type in each of the implementation modules, created with its `from_content_encoding`
+//! constructor it undoes each of the listed encodings in turn.
+//!
+//! Only the `br`, `deflate`, `gzip` (or `x-gzip`), `zstd` and `identity` content codings are
+//! supported, each requiring the feature for its algorithm to be enabled. As specified for HTTP the
+//! `deflate` coding is the [zlib](crate::Algorithm::Zlib) format.
+
+use crate::{error::UnsupportedEncoding, Algorithm};
+use std::io::Result;
+
+/// The supported content codings, in the order they are preferred when a client accepts several
+/// equally.
+const CODINGS: &[(&str, Algorithm)] = &[
+    #[cfg(feature = "zstd")]
+    ("zstd", Algorithm::Zstd),
+    #[cfg(feature = "brotli")]
+    ("br", Algorithm::Brotli),
+    #[cfg(feature = "gzip")]
+    ("gzip", Algorithm::Gzip),
+    #[cfg(feature = "zlib")]
+    ("deflate", Algorithm::Zlib),
+];
+
+/// Returns the name of the content coding for `algorithm`, or `None` if it has no registered
+/// HTTP content coding.
+///
+/// ```
+/// # #[cfg(feature = "brotli")] {
+/// use async_compression::{http, Algorithm};
+///
+/// assert_eq!(http::content_coding(Algorithm::Brotli), Some("br"));
+/// # }
+/// ```
+pub fn content_coding(algorithm: Algorithm) -> Option<&'static str> {
+    CODINGS
+        .iter()
+        .find(|&&(_, candidate)| candidate == algorithm)
+        .map(|&(name, _)| name)
+}
+
+/// Returns the algorithm for a content coding, or `None` for `identity`.
+fn coding_algorithm(coding: &str) -> std::result::Result<Option<Algorithm>, UnsupportedEncoding> {
+    if coding.eq_ignore_ascii_case("identity") {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        if coding.eq_ignore_ascii_case("x-gzip") {
+            return Ok(Some(Algorithm::Gzip));
+        }
+    }
+
+    CODINGS
+        .iter()
+        .find(|&&(name, _)| coding.eq_ignore_ascii_case(name))
+        .map(|&(_, algorithm)| Some(algorithm))
+        .ok_or_else(|| UnsupportedEncoding {
+            encoding: coding.to_owned(),
+        })
+}
+
+/// Parses the value of a `Content-Encoding` header into the algorithms that were applied, in the
+/// order they were applied, with any `identity` codings removed.
+///
+/// Fails with an [`UnsupportedEncoding`](crate::UnsupportedEncoding) error if any of the codings
+/// is not supported, as the body can then not be decoded.
+///
+/// ```
+/// # #[cfg(all(feature = "gzip", feature = "brotli"))] {
+/// use async_compression::{http, Algorithm};
+///
+/// let encodings = http::parse_content_encoding("gzip, br")?;
+/// assert_eq!(encodings, [Algorithm::Gzip, Algorithm::Brotli]);
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn parse_content_encoding(value: &str) -> Result<Vec<Algorithm>> {
+    let mut encodings = Vec::new();
+    for coding in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        encodings.extend(coding_algorithm(coding)?);
+    }
+    Ok(encodings)
+}
+
+/// Parses a `qvalue`, returning it in thousandths.
+fn parse_qvalue(value: &str) -> Option<u16> {
+    let (integer, fraction) = match value.find('.') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let integer = match integer {
+        "0" => 0,
+        "1" => 1000,
+        _ => return None,
+    };
+    let fraction = fraction
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(3)
+        .fold(0, |acc, b| acc * 10 + u16::from(b - b'0'));
+
+    Some(integer + fraction).filter(|&q| q <= 1000)
+}
+
+/// Picks the preferred supported encoding that is acceptable according to the value of an
+/// `Accept-Encoding` header, taking into account the `q` weights the client gave.
+///
+/// Returns `None` if none of the supported encodings are acceptable, in which case the response
+/// should not be encoded. When the client accepts several encodings equally the order of
+/// preference is zstd, brotli, gzip then deflate.
+///
+/// ```
+/// # #[cfg(all(feature = "gzip", feature = "brotli"))] {
+/// use async_compression::{http, Algorithm};
+///
+/// assert_eq!(http::select_encoding("gzip;q=1.0, br;q=0.5"), Some(Algorithm::Gzip));
+/// assert_eq!(http::select_encoding("identity"), None);
+/// # }
+/// ```
+pub fn select_encoding(accept_encoding: &str) -> Option<Algorithm> {
+    let mut weights = Vec::new();
+    let mut wildcard = None;
+
+    for element in accept_encoding
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let mut params = element.split(';').map(str::trim);
+        let coding = params.next().unwrap_or_default();
+        let weight = params
+            .filter_map(|param| {
+                let (name, value) = param.split_at(param.find('=')?);
+                Some((name.trim(), value[1..].trim()))
+            })
+            .find(|(name, _)| name.eq_ignore_ascii_case("q"))
+            .map_or(Some(1000), |(_, value)| parse_qvalue(value));
+
+        // Elements with an invalid weight are ignored
+        let weight = match weight {
+            Some(weight) => weight,
+            None => continue,
+        };
+
+        if coding == "*" {
+            wildcard = Some(weight);
+        } else if let Ok(Some(algorithm)) = coding_algorithm(coding) {
+            weights.push((algorithm, weight));
+        }
+    }
+
+    let (mut best, mut best_weight) = (None, 0);
+    for &(_, algorithm) in CODINGS {
+        let weight = weights
+            .iter()
+            .find(|&&(candidate, _)| candidate == algorithm)
+            .map(|&(_, weight)| weight)
+            .or(wildcard)
+            .unwrap_or(0);
+        if weight > best_weight {
+            best = Some(algorithm);
+            best_weight = weight;
+        }
+    }
+    best
+}
@@ -61,6 +61,14 @@
     not(feature = "tokio-02"),
     doc = "`tokio-02` (*inactive*) | `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
 )]
+#![cfg_attr(
+    feature = "tokio",
+    doc = "[`tokio`](crate::tokio) | [`tokio::io::AsyncBufRead`](::tokio::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio::io::AsyncWrite)"
+)]
+#![cfg_attr(
+    not(feature = "tokio"),
+    doc = "`tokio` (*inactive*) | `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
+)]
 //!
 
 //! ## Compression algorithm
@@ -103,6 +111,22 @@
     not(feature = "gzip"),
     doc = "`gzip` (*inactive*) | `GzipEncoder`, `GzipDecoder`"
 )]
+#![cfg_attr(
+    feature = "lz4",
+    doc = "`lz4` | [`Lz4Encoder`](?search=Lz4Encoder), [`Lz4Decoder`](?search=Lz4Decoder)"
+)]
+#![cfg_attr(
+    not(feature = "lz4"),
+    doc = "`lz4` (*inactive*) | `Lz4Encoder`, `Lz4Decoder`"
+)]
+#![cfg_attr(
+    feature = "lzip",
+    doc = "`lzip` | [`LzipEncoder`](?search=LzipEncoder), [`LzipDecoder`](?search=LzipDecoder)"
+)]
+#![cfg_attr(
+    not(feature = "lzip"),
+    doc = "`lzip` (*inactive*) | `LzipEncoder`, `LzipDecoder`"
+)]
 #![cfg_attr(
     feature = "lzma",
     doc = "`lzma` | [`LzmaEncoder`](?search=LzmaEncoder), [`LzmaDecoder`](?search=LzmaDecoder)"
@@ -111,6 +135,14 @@
     not(feature = "lzma"),
     doc = "`lzma` (*inactive*) | `LzmaEncoder`, `LzmaDecoder`"
 )]
+#![cfg_attr(
+    feature = "lzma2",
+    doc = "`lzma2` | [`Lzma2Encoder`](?search=Lzma2Encoder), [`Lzma2Decoder`](?search=Lzma2Decoder)"
+)]
+#![cfg_attr(
+    not(feature = "lzma2"),
+    doc = "`lzma2` (*inactive*) | `Lzma2Encoder`, `Lzma2Decoder`"
+)]
 #![cfg_attr(
     feature = "xz",
     doc = "`xz` | [`XzEncoder`](?search=XzEncoder), [`XzDecoder`](?search=XzDecoder)"
@@ -136,6 +168,18 @@
     doc = "`zstd` (*inactive*) | `ZstdEncoder`, `ZstdDecoder`"
 )]
 //!
+//! The additional `zstdmt` feature builds libzstd with multithreading support, allowing the zstd
+//! encoders to compress using worker threads, see `ZstdParams::nb_workers`.
+//!
+//! Each module also has [`AnyEncoder`](?search=AnyEncoder) and
+//! [`AnyDecoder`](?search=AnyDecoder) types, which support all of the enabled algorithms with the
+//! one to use chosen at runtime by an [`Algorithm`], and an [`AutoDecoder`](?search=AutoDecoder)
+//! type which detects the algorithm from the data itself.
+//!
+//! With the `gzip` feature enabled each module also has a
+//! [`ParallelGzipEncoder`](?search=ParallelGzipEncoder) type, which compresses blocks of the input
+//! on multiple threads.
+//!
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(
@@ -148,20 +192,55 @@
 
 #[macro_use]
 mod macros;
-mod codec;
+pub mod codec;
 
 #[cfg(feature = "futures-io")]
 #[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
 pub mod futures;
+pub mod http;
 #[cfg(feature = "stream")]
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 pub mod stream;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod tokio;
 #[cfg(feature = "tokio-02")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio-02")))]
 pub mod tokio_02;
 
+mod error;
+mod params;
 mod unshared;
-mod util;
+pub mod util;
+
+pub use crate::error::{LimitExceeded, UnsupportedEncoding};
+pub use crate::params::Algorithm;
+
+#[cfg(feature = "zlib")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zlib")))]
+pub use crate::error::DictionaryRequired;
+
+#[cfg(feature = "brotli")]
+#[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+pub use crate::params::{BrotliMode, BrotliParams};
+#[cfg(any(feature = "deflate", feature = "gzip", feature = "zlib"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "deflate", feature = "gzip", feature = "zlib")))
+)]
+pub use crate::params::{FlateParams, FlateStrategy};
+#[cfg(feature = "gzip")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+pub use crate::params::{GzipHeader, GzipHeaderBuilder};
+#[cfg(feature = "lz4")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+pub use crate::params::{Lz4BlockSize, Lz4Params};
+#[cfg(feature = "xz")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xz")))]
+pub use crate::params::{XzCheck, XzFilter, XzMode, XzParams};
+#[cfg(feature = "zstd")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+pub use crate::params::{ZstdDecoderDictionary, ZstdEncoderDictionary, ZstdParams, ZstdStrategy};
 
 #[cfg(feature = "brotli")]
 use brotli::enc::backward_references::BrotliEncoderParams;
@@ -216,6 +295,16 @@ impl Level {
         }
     }
 
+    #[cfg(feature = "lz4")]
+    fn into_lz4(self) -> u32 {
+        match self {
+            Self::Fastest => 0,
+            Self::Best => 12,
+            Self::Precise(quality) => quality.min(12),
+            Self::Default => 0,
+        }
+    }
+
     #[cfg(feature = "zstd")]
     fn into_zstd(self) -> i32 {
         match self {
@@ -226,7 +315,7 @@ impl Level {
         }
     }
 
-    #[cfg(feature = "xz2")]
+    #[cfg(feature = "lzma-sys")]
     fn into_xz2(self) -> u32 {
         match self {
             Self::Fastest => 0,
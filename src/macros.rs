@@ -1,12 +1,21 @@
 macro_rules! algos {
-    (@algo $algo:ident [$algo_s:expr] $decoder:ident $encoder:ident<$inner:ident> $({ $($constructor:tt)* })*) => {
+    (@algo [$($mod:ident)::+] $algo:ident [$algo_s:expr] $decoder:ident $encoder:ident<$inner:ident> $({ $($constructor:tt)* })* $(decoder { $($method:tt)* })*) => {
         #[cfg(feature = $algo_s)]
         decoder! {
             /// A
             #[doc = $algo_s]
             /// decoder, or decompressor.
             #[cfg_attr(docsrs, doc(cfg(feature = $algo_s)))]
-            $decoder
+            $decoder {
+                pub fn new(inner: $inner) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::$decoder::new(),
+                        ),
+                    }
+                }
+            } $({ $($method)* })*
         }
 
         #[cfg(feature = $algo_s)]
@@ -24,19 +33,38 @@ macro_rules! algos {
     };
 
     ($($mod:ident)::+<$inner:ident>) => {
-        algos!(@algo brotli ["brotli"] BrotliDecoder BrotliEncoder<$inner> {
+        algos!(@algo [$($mod)::+] brotli ["brotli"] BrotliDecoder BrotliEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
-                let params = brotli::enc::backward_references::BrotliEncoderParams::default();
+                Self::with_quality_and_params(inner, level, crate::BrotliParams::default())
+            }
+
+            /// The window size, mode and size hint are configured by `params`, see
+            /// [`BrotliParams`](crate::BrotliParams).
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::BrotliParams,
+            ) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
                         inner,
-                        crate::codec::BrotliEncoder::new(level.into_brotli(params)),
+                        crate::codec::BrotliEncoder::new(
+                            level.into_brotli(params.into_encoder_params()),
+                        ),
                     ),
                 }
             }
+        } decoder {
+            /// Configure whether streams using windows larger than 16 MiB are accepted, as
+            /// produced with [`BrotliParams::large_window`](crate::BrotliParams::large_window).
+            /// This is a non-standard extension so they are rejected by default. It must be
+            /// configured before any data is decoded.
+            pub fn large_window(&mut self, enabled: bool) {
+                self.inner.get_decoder_mut().large_window(enabled);
+            }
         });
 
-        algos!(@algo bzip2 ["bzip2"] BzDecoder BzEncoder<$inner> {
+        algos!(@algo [$($mod)::+] bzip2 ["bzip2"] BzDecoder BzEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -47,7 +75,7 @@ macro_rules! algos {
             }
         });
 
-        algos!(@algo deflate ["deflate"] DeflateDecoder DeflateEncoder<$inner> {
+        algos!(@algo [$($mod)::+] deflate ["deflate"] DeflateDecoder DeflateEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -56,9 +84,59 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Compression uses the preset `dictionary`, which the decoder must also be given as
+            /// there is nothing in a raw deflate stream to identify it. Only the last 32 KiB of the
+            /// dictionary can be referenced. This fails if the dictionary can not be loaded.
+            pub fn with_quality_and_dictionary(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::DeflateEncoder::with_dictionary(level.into_flate2(), dictionary)?,
+                    ),
+                })
+            }
+        } {
+            /// Compression uses the window size, memory level and strategy in `params`, see
+            /// [`FlateParams`](crate::FlateParams).
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::FlateParams,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::DeflateEncoder::with_params(level.into_flate2(), params),
+                    ),
+                }
+            }
+        } decoder {
+            /// Creates a new decoder which decompresses data that was compressed with the preset
+            /// `dictionary`. This fails if the dictionary can not be loaded.
+            pub fn with_dictionary(inner: $inner, dictionary: &[u8]) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::DeflateDecoder::with_dictionary(dictionary)?,
+                    ),
+                })
+            }
+
+            /// Sets the base 2 logarithm of the largest window streams may use, from 8 to 15 (the
+            /// default). Values outside the range are clamped. Streams referencing data further
+            /// back than that are rejected, as with zlib's `windowBits` for `inflateInit2`. This
+            /// must be set before decoding starts.
+            pub fn window_bits(&mut self, bits: u32) {
+                self.inner.get_decoder_mut().window_bits(bits);
+            }
         });
 
-        algos!(@algo gzip ["gzip"] GzipDecoder GzipEncoder<$inner> {
+        algos!(@algo [$($mod)::+] gzip ["gzip"] GzipDecoder GzipEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -67,9 +145,153 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// The header written before the compressed data is described by `header`, see
+            /// [`GzipHeaderBuilder`](crate::GzipHeaderBuilder).
+            pub fn with_quality_and_header(
+                inner: $inner,
+                level: crate::Level,
+                header: crate::GzipHeader,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::GzipEncoder::with_header(level.into_flate2(), &header),
+                    ),
+                }
+            }
+        } {
+            /// Compression uses the window size, memory level and strategy in `params`, see
+            /// [`FlateParams`](crate::FlateParams).
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::FlateParams,
+            ) -> Self {
+                Self::with_quality_header_and_params(
+                    inner,
+                    level,
+                    crate::GzipHeader::default(),
+                    params,
+                )
+            }
+
+            /// Writes `header` before the compressed data, as
+            /// [`with_quality_and_header`](Self::with_quality_and_header), and compresses using
+            /// the window size, memory level and strategy in `params`.
+            pub fn with_quality_header_and_params(
+                inner: $inner,
+                level: crate::Level,
+                header: crate::GzipHeader,
+                params: crate::FlateParams,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::GzipEncoder::with_header_and_params(
+                            level.into_flate2(),
+                            &header,
+                            params,
+                        ),
+                    ),
+                }
+            }
+        } decoder {
+            /// Returns the header of the current gzip member, or `None` if it has not been read
+            /// yet.
+            ///
+            /// When decoding multiple members this is replaced by the header of each following
+            /// member as it is read, output from different members is never returned together so
+            /// it always matches the data just returned.
+            pub fn header(&self) -> Option<&crate::GzipHeader> {
+                self.inner.get_decoder_ref().header()
+            }
+
+            /// Sets the base 2 logarithm of the largest window streams may use, from 8 to 15 (the
+            /// default). Values outside the range are clamped. Streams referencing data further
+            /// back than that are rejected, as with zlib's `windowBits` for `inflateInit2`. This
+            /// must be set before decoding starts.
+            pub fn window_bits(&mut self, bits: u32) {
+                self.inner.get_decoder_mut().window_bits(bits);
+            }
+        });
+
+        #[cfg(feature = "gzip")]
+        encoder! {
+            /// A gzip encoder, or compressor, which compresses blocks of the input in parallel on
+            /// multiple threads, like `pigz`.
+            ///
+            /// The input is split into blocks (128 KiB by default) which are each compressed by
+            /// one of a pool of worker threads, primed with the 32 KiB of input preceding them so
+            /// the compression ratio stays close to that of a single stream. The blocks are
+            /// joined into a single gzip member which any gzip decoder can decompress.
+            ///
+            /// The worker threads are shared by every parallel encoder in the process, the pool is
+            /// grown to at least `threads` workers when this encoder sends its first block and the
+            /// workers are kept for reuse afterwards. `threads` is limited to the number of cores
+            /// reported by [`std::thread::available_parallelism`], so the pool never holds more
+            /// workers than that. At most `threads * 2` of this encoder's blocks are in flight at
+            /// once. The calling thread never blocks on the workers: while they
+            /// are busy, and when flushing or finishing before they are done, the task is woken
+            /// once the next block completes.
+            #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+            ParallelGzipEncoder<$inner> {
+                pub fn new(inner: $inner, threads: usize) -> Self {
+                    Self::with_quality(inner, crate::Level::Default, threads)
+                }
+            } {
+                pub fn with_quality(inner: $inner, level: crate::Level, threads: usize) -> Self {
+                    Self::with_quality_and_block_size(
+                        inner,
+                        level,
+                        threads,
+                        crate::codec::PARALLEL_GZIP_BLOCK_SIZE,
+                    )
+                }
+            } {
+                /// The input is split into blocks of `block_size` bytes, smaller blocks allow
+                /// more parallelism on small inputs at some cost to the compression ratio.
+                pub fn with_quality_and_block_size(
+                    inner: $inner,
+                    level: crate::Level,
+                    threads: usize,
+                    block_size: usize,
+                ) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Encoder::new(
+                            inner,
+                            crate::codec::ParallelGzipEncoder::new(
+                                level.into_flate2(),
+                                threads,
+                                block_size,
+                            ),
+                        ),
+                    }
+                }
+            }
+        }
+
+        algos!(@algo [$($mod)::+] lz4 ["lz4"] Lz4Decoder Lz4Encoder<$inner> {
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self::with_quality_and_params(inner, level, crate::Lz4Params::default())
+            }
+
+            /// The frame format is configured by `params`, see [`Lz4Params`](crate::Lz4Params).
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::Lz4Params,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::Lz4Encoder::new(params.into_preferences(level.into_lz4())),
+                    ),
+                }
+            }
         });
 
-        algos!(@algo zlib ["zlib"] ZlibDecoder ZlibEncoder<$inner> {
+        algos!(@algo [$($mod)::+] zlib ["zlib"] ZlibDecoder ZlibEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -78,9 +300,65 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Compression uses the preset `dictionary`, which the decoder must also be given. The
+            /// stream header records the dictionary's Adler-32 so decoders can tell which one is
+            /// needed. Only the last 32 KiB of the dictionary can be referenced. This fails if the
+            /// dictionary can not be loaded.
+            pub fn with_quality_and_dictionary(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZlibEncoder::with_dictionary(level.into_flate2(), dictionary)?,
+                    ),
+                })
+            }
+        } {
+            /// Compression uses the window size, memory level and strategy in `params`, see
+            /// [`FlateParams`](crate::FlateParams). The window size is recorded in the stream
+            /// header.
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::FlateParams,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZlibEncoder::with_params(level.into_flate2(), params),
+                    ),
+                }
+            }
+        } decoder {
+            /// Creates a new decoder which decompresses streams that were compressed with the
+            /// preset `dictionary`, streams without one are still accepted.
+            ///
+            /// Decoding a stream whose header names a different dictionary fails with an error
+            /// wrapping [`DictionaryRequired`](crate::DictionaryRequired), as does decoding any
+            /// stream that needs a dictionary with a decoder created without one.
+            pub fn with_dictionary(inner: $inner, dictionary: &[u8]) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZlibDecoder::with_dictionary(dictionary),
+                    ),
+                }
+            }
+
+            /// Sets the base 2 logarithm of the largest window streams may use, from 8 to 15 (the
+            /// default). Values outside the range are clamped. Streams whose header declares a
+            /// larger window are rejected, as with zlib's `windowBits` for `inflateInit2`. This
+            /// must be set before decoding starts.
+            pub fn window_bits(&mut self, bits: u32) {
+                self.inner.get_decoder_mut().window_bits(bits);
+            }
         });
 
-        algos!(@algo zstd ["zstd"] ZstdDecoder ZstdEncoder<$inner> {
+        algos!(@algo [$($mod)::+] zstd ["zstd"] ZstdDecoder ZstdEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -89,9 +367,96 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Compression uses the raw `dictionary`, which the decoder must also be given. This
+            /// fails if the dictionary can not be loaded.
+            ///
+            /// The dictionary is digested each time this is called, when creating many encoders
+            /// with the same dictionary prefer
+            /// [`with_prepared_dictionary`](Self::with_prepared_dictionary).
+            pub fn with_quality_and_dictionary(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_dictionary(level.into_zstd(), dictionary)?,
+                    ),
+                })
+            }
+        } {
+            /// Compression uses the already digested `dictionary`, at the level it was digested
+            /// for, see [`ZstdEncoderDictionary`](crate::ZstdEncoderDictionary).
+            pub fn with_prepared_dictionary(
+                inner: $inner,
+                dictionary: &crate::ZstdEncoderDictionary,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_prepared_dictionary(dictionary)?,
+                    ),
+                })
+            }
+        } {
+            /// Compression uses the advanced options in `params` on top of the defaults for
+            /// `level`. This fails if libzstd rejects one of the options, e.g. a window log out of
+            /// range.
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::ZstdParams,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_params(level.into_zstd(), params)?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Creates a new decoder which decompresses data that was compressed with the raw
+            /// `dictionary`. This fails if the dictionary can not be loaded.
+            ///
+            /// The dictionary is digested each time this is called, when creating many decoders
+            /// with the same dictionary prefer
+            /// [`with_prepared_dictionary`](Self::with_prepared_dictionary).
+            pub fn with_dictionary(inner: $inner, dictionary: &[u8]) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZstdDecoder::with_dictionary(dictionary)?,
+                    ),
+                })
+            }
+
+            /// Creates a new decoder which decompresses data that was compressed with the already
+            /// digested `dictionary`, see [`ZstdDecoderDictionary`](crate::ZstdDecoderDictionary).
+            pub fn with_prepared_dictionary(
+                inner: $inner,
+                dictionary: &crate::ZstdDecoderDictionary,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZstdDecoder::with_prepared_dictionary(dictionary)?,
+                    ),
+                })
+            }
+
+            /// Sets the base 2 logarithm of the largest window frames may use (`windowLogMax`),
+            /// frames with a larger window are rejected to limit memory usage. The default is 27
+            /// (128 MiB), data compressed with a larger
+            /// [`ZstdParams::window_log`](crate::ZstdParams::window_log) needs this raised. This
+            /// fails if libzstd rejects the value.
+            pub fn window_log_max(&mut self, log: u32) -> std::io::Result<()> {
+                self.inner.get_decoder_mut().window_log_max(log)
+            }
         });
 
-        algos!(@algo xz ["xz"] XzDecoder XzEncoder<$inner> {
+        algos!(@algo [$($mod)::+] xz ["xz"] XzDecoder XzEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -100,9 +465,34 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Compression uses the options in `params`, see [`XzParams`](crate::XzParams). This
+            /// fails if liblzma rejects them, e.g. LZMA2 options out of range or not enough
+            /// memory for the requested number of threads.
+            pub fn with_quality_and_params(
+                inner: $inner,
+                level: crate::Level,
+                params: crate::XzParams,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::XzEncoder::with_params(level.into_xz2(), params)?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Limits the memory in bytes this decoder may allocate, mostly for the dictionary
+            /// whose size is chosen when compressing. Data needing more fails with a
+            /// [`LimitExceeded::MemoryUsage`](crate::LimitExceeded::MemoryUsage) error. There is
+            /// no limit by default. The limit may be changed part way through decoding, a limit
+            /// below what the data being decoded already needs fails the next read.
+            pub fn max_memory_usage(&mut self, limit: Option<u64>) {
+                self.inner.get_decoder_mut().max_memory_usage(limit);
+            }
         });
 
-        algos!(@algo lzma ["lzma"] LzmaDecoder LzmaEncoder<$inner> {
+        algos!(@algo [$($mod)::+] lzma ["lzma"] LzmaDecoder LzmaEncoder<$inner> {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -111,6 +501,152 @@ macro_rules! algos {
                     ),
                 }
             }
+        } decoder {
+            /// Limits the memory in bytes this decoder may allocate, mostly for the dictionary
+            /// whose size is chosen when compressing. Data needing more fails with a
+            /// [`LimitExceeded::MemoryUsage`](crate::LimitExceeded::MemoryUsage) error. There is
+            /// no limit by default. The limit may be changed part way through decoding, a limit
+            /// below what the data being decoded already needs fails the next read.
+            pub fn max_memory_usage(&mut self, limit: Option<u64>) {
+                self.inner.get_decoder_mut().max_memory_usage(limit);
+            }
+        });
+
+        algos!(@algo [$($mod)::+] lzip ["lzip"] LzipDecoder LzipEncoder<$inner> {
+            /// The LZMA encoder can't flush without ending its stream, so each flush ends the
+            /// current lzip member and any further data is written as a new member. Decoders must
+            /// have `multiple_members` enabled to read past the first flush.
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::LzipEncoder::new(level.into_xz2()),
+                    ),
+                }
+            }
         });
+
+        algos!(@algo [$($mod)::+] lzma2 ["lzma2"] Lzma2Decoder Lzma2Encoder<$inner> {
+            /// The dictionary size is chosen by `level`, as with the xz presets, and must be
+            /// passed on to the decoder by whatever container the raw data is stored in.
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::Lzma2Encoder::new(level.into_xz2()),
+                    ),
+                }
+            }
+        } decoder {
+            /// Creates a new decoder for data compressed with a dictionary of at most `dict_size`
+            /// bytes, which is also the memory allocated for it. Decoders created with `new`
+            /// accept the 64 MiB dictionary of the highest level. This fails if liblzma can't
+            /// allocate the dictionary.
+            pub fn with_dict_size(inner: $inner, dict_size: u32) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::Lzma2Decoder::with_dict_size(dict_size)?,
+                    ),
+                })
+            }
+        });
+
+        decoder! {
+            /// A decoder, or decompressor, for any of the algorithms enabled in this build, chosen
+            /// at runtime by an [`Algorithm`](crate::Algorithm).
+            AnyDecoder {
+                pub fn new(inner: $inner, algorithm: crate::Algorithm) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::AnyDecoder::new(algorithm),
+                        ),
+                    }
+                }
+            }
+        }
+
+        decoder! {
+            /// A decoder, or decompressor, which detects the algorithm from the signature at the
+            /// start of the compressed data.
+            ///
+            /// The bzip2, gzip, LZ4, lzip, lzma, xz, zlib and zstd formats are detected when their
+            /// features are enabled, other input fails with an
+            /// [`InvalidData`](std::io::ErrorKind::InvalidData) error unless
+            /// [`passthrough`](Self::passthrough) is enabled.
+            AutoDecoder {
+                pub fn new(inner: $inner) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::AutoDecoder::new(),
+                        ),
+                    }
+                }
+            } {
+                /// Configure whether input in an unrecognized format is passed through unchanged
+                /// instead of failing, this must be set before any data is decoded.
+                pub fn passthrough(&mut self, enabled: bool) {
+                    self.inner.get_decoder_mut().passthrough(enabled);
+                }
+
+                /// Returns the algorithm detected from the start of the data, or `None` if it has
+                /// not been read yet or the data is being passed through unchanged.
+                pub fn algorithm(&self) -> Option<crate::Algorithm> {
+                    self.inner.get_decoder_ref().algorithm()
+                }
+            }
+        }
+
+        decoder! {
+            /// A decoder, or decompressor, for data which had multiple algorithms applied one
+            /// after the other, such as an HTTP body with multiple `Content-Encoding`s, see the
+            /// [`http`](crate::http) module.
+            ChainDecoder {
+                /// The `encodings` are given in the order they were applied to the data, this
+                /// decoder undoes them in reverse order. With no encodings the data is passed
+                /// through unchanged.
+                pub fn new(inner: $inner, encodings: &[crate::Algorithm]) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::ChainDecoder::new(encodings),
+                        ),
+                    }
+                }
+            } {
+                /// Creates a new decoder for data with the encodings listed in the value of a
+                /// `Content-Encoding` header, see
+                /// [`http::parse_content_encoding`](crate::http::parse_content_encoding) for when
+                /// this fails.
+                pub fn from_content_encoding(inner: $inner, value: &str) -> std::io::Result<Self> {
+                    Ok(Self::new(inner, &crate::http::parse_content_encoding(value)?))
+                }
+            }
+        }
+
+        encoder! {
+            /// An encoder, or compressor, for any of the algorithms enabled in this build, chosen
+            /// at runtime by an [`Algorithm`](crate::Algorithm).
+            AnyEncoder<$inner> {
+                pub fn new(inner: $inner, algorithm: crate::Algorithm) -> Self {
+                    Self::with_quality(inner, algorithm, crate::Level::Default)
+                }
+            } {
+                pub fn with_quality(
+                    inner: $inner,
+                    algorithm: crate::Algorithm,
+                    level: crate::Level,
+                ) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Encoder::new(
+                            inner,
+                            crate::codec::AnyEncoder::new(algorithm, level),
+                        ),
+                    }
+                }
+            }
+        }
     }
 }
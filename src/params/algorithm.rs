@@ -0,0 +1,59 @@
+/// One of the compression algorithms enabled in this build, used to pick an algorithm at runtime
+/// with the `AnyEncoder` and `AnyDecoder` types.
+///
+/// ```
+/// # #[cfg(all(feature = "gzip", feature = "futures-io"))] {
+/// use async_compression::{futures::bufread::AnyDecoder, Algorithm};
+///
+/// let algorithm = Algorithm::Gzip;
+/// let decoder = AnyDecoder::new(&b""[..], algorithm);
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// The brotli format.
+    #[cfg(feature = "brotli")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+    Brotli,
+    /// The bzip2 format.
+    #[cfg(feature = "bzip2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bzip2")))]
+    Bzip2,
+    /// The raw deflate format.
+    #[cfg(feature = "deflate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    Deflate,
+    /// The gzip format.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    Gzip,
+    /// The LZ4 frame format.
+    #[cfg(feature = "lz4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+    Lz4,
+    /// The lzip format.
+    #[cfg(feature = "lzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lzip")))]
+    Lzip,
+    /// The legacy `.lzma` format.
+    #[cfg(feature = "lzma")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lzma")))]
+    Lzma,
+    /// Raw LZMA2 data, without the xz container.
+    #[cfg(feature = "lzma2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lzma2")))]
+    Lzma2,
+    /// The xz format.
+    #[cfg(feature = "xz")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "xz")))]
+    Xz,
+    /// The zlib format.
+    #[cfg(feature = "zlib")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zlib")))]
+    Zlib,
+    /// The zstd format.
+    #[cfg(feature = "zstd")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+    Zstd,
+}
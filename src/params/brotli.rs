@@ -0,0 +1,89 @@
+use brotli::enc::backward_references::{BrotliEncoderMode, BrotliEncoderParams};
+
+/// A hint about the kind of data being compressed, used by the brotli encoders to tune their
+/// heuristics.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrotliMode {
+    /// No assumptions about the data, the default.
+    Generic,
+    /// UTF-8 formatted text.
+    Text,
+    /// WOFF 2.0 fonts.
+    Font,
+}
+
+/// Window, mode and size hint options for the brotli encoders.
+///
+/// The defaults match the reference encoder: a 4 MiB window (`lgwin` of 22), generic mode and no
+/// size hint.
+///
+/// ```
+/// use async_compression::{BrotliMode, BrotliParams};
+///
+/// let params = BrotliParams::default()
+///     .window_bits(24)
+///     .mode(BrotliMode::Text)
+///     .size_hint(1 << 20);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BrotliParams {
+    window_bits: Option<u32>,
+    mode: Option<BrotliMode>,
+    size_hint: Option<usize>,
+    large_window: bool,
+}
+
+impl BrotliParams {
+    /// Sets the base 2 logarithm of the sliding window size (`lgwin`), from 10 to 24 or up to 30
+    /// with [`large_window`](Self::large_window) enabled. Values outside the range are clamped.
+    ///
+    /// A larger window can improve the compression ratio, but the decoder needs that much memory
+    /// to decompress the data.
+    pub fn window_bits(mut self, bits: u32) -> Self {
+        self.window_bits = Some(bits);
+        self
+    }
+
+    /// Sets a hint about the kind of data being compressed.
+    pub fn mode(mut self, mode: BrotliMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the expected total size of the uncompressed data, which lets the encoder pick better
+    /// parameters. The data may be larger or smaller than the hint.
+    pub fn size_hint(mut self, size: usize) -> Self {
+        self.size_hint = Some(size);
+        self
+    }
+
+    /// Sets whether windows larger than 16 MiB may be used, allowing a
+    /// [`window_bits`](Self::window_bits) of up to 30.
+    ///
+    /// This produces a non-standard stream which the decoder must also be configured to accept
+    /// with its `large_window` option, most other brotli decoders will reject it.
+    pub fn large_window(mut self, enabled: bool) -> Self {
+        self.large_window = enabled;
+        self
+    }
+
+    pub(crate) fn into_encoder_params(self) -> BrotliEncoderParams {
+        let mut params = BrotliEncoderParams::default();
+        if let Some(bits) = self.window_bits {
+            params.lgwin = bits.min(30) as i32;
+        }
+        if let Some(mode) = self.mode {
+            params.mode = match mode {
+                BrotliMode::Generic => BrotliEncoderMode::BROTLI_MODE_GENERIC,
+                BrotliMode::Text => BrotliEncoderMode::BROTLI_MODE_TEXT,
+                BrotliMode::Font => BrotliEncoderMode::BROTLI_MODE_FONT,
+            };
+        }
+        if let Some(size) = self.size_hint {
+            params.size_hint = size;
+        }
+        params.large_window = self.large_window;
+        params
+    }
+}
@@ -0,0 +1,88 @@
+use std::os::raw::c_int;
+
+use libz_sys::{Z_DEFAULT_STRATEGY, Z_FILTERED, Z_FIXED, Z_HUFFMAN_ONLY, Z_RLE};
+
+/// How the deflate, zlib and gzip encoders search for matches, as with zlib's `strategy`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlateStrategy {
+    /// `Z_DEFAULT_STRATEGY`, for general data.
+    Default,
+    /// `Z_FILTERED`, only uses matches of at least 5 bytes. Suits data produced by a filter or
+    /// predictor, with mostly small and somewhat random values.
+    Filtered,
+    /// `Z_HUFFMAN_ONLY`, only Huffman codes the bytes without looking for matches.
+    HuffmanOnly,
+    /// `Z_RLE`, only looks for matches with a distance of 1, i.e. runs of the same byte. Almost
+    /// as fast as `HuffmanOnly` while still compressing image data such as PNG scanlines well.
+    Rle,
+    /// `Z_FIXED`, only uses the fixed Huffman codes from the specification, which saves
+    /// decoders from having to build code tables.
+    Fixed,
+}
+
+/// Window size, memory level and strategy options for the deflate, zlib and gzip encoders, as
+/// passed to zlib's `deflateInit2`.
+///
+/// The defaults match zlib's: a 32 KiB window (`windowBits` of 15), a `memLevel` of 8 and the
+/// default strategy.
+///
+/// ```
+/// use async_compression::{FlateParams, FlateStrategy};
+///
+/// let params = FlateParams::default()
+///     .window_bits(10)
+///     .strategy(FlateStrategy::Rle);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlateParams {
+    window_bits: Option<u32>,
+    mem_level: Option<u32>,
+    strategy: Option<FlateStrategy>,
+}
+
+impl FlateParams {
+    /// Sets the base 2 logarithm of the window size (`windowBits`), from 9 to 15. Values outside
+    /// the range are clamped. The zlib encoders record the window size in the stream header.
+    ///
+    /// A smaller window lets the data be decompressed by peers with less memory, at the cost of
+    /// compression ratio.
+    pub fn window_bits(mut self, bits: u32) -> Self {
+        self.window_bits = Some(bits);
+        self
+    }
+
+    /// Sets zlib's `memLevel`, from 1 to 9, which trades memory for speed and compression ratio.
+    /// Values outside the range are clamped.
+    pub fn mem_level(mut self, level: u32) -> Self {
+        self.mem_level = Some(level);
+        self
+    }
+
+    /// Sets the match finding strategy.
+    pub fn strategy(mut self, strategy: FlateStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// The effective `windowBits`.
+    pub(crate) fn window_bits_or_default(&self) -> u32 {
+        self.window_bits.map_or(15, |bits| bits.clamp(9, 15))
+    }
+
+    /// The effective `memLevel`.
+    pub(crate) fn mem_level_or_default(&self) -> c_int {
+        self.mem_level.map_or(8, |level| level.clamp(1, 9)) as c_int
+    }
+
+    /// The zlib `strategy`.
+    pub(crate) fn zlib_strategy(&self) -> c_int {
+        match self.strategy.unwrap_or(FlateStrategy::Default) {
+            FlateStrategy::Default => Z_DEFAULT_STRATEGY,
+            FlateStrategy::Filtered => Z_FILTERED,
+            FlateStrategy::HuffmanOnly => Z_HUFFMAN_ONLY,
+            FlateStrategy::Rle => Z_RLE,
+            FlateStrategy::Fixed => Z_FIXED,
+        }
+    }
+}
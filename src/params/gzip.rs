@@ -0,0 +1,171 @@
+/// The header of a gzip member, as described in [RFC 1952 section 2.3].
+///
+/// [RFC 1952 section 2.3]: https://tools.ietf.org/html/rfc1952#section-2.3
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GzipHeader {
+    pub(crate) text: bool,
+    pub(crate) mtime: u32,
+    pub(crate) extra_flags: u8,
+    pub(crate) operating_system: u8,
+    pub(crate) extra: Option<Vec<u8>>,
+    pub(crate) filename: Option<Vec<u8>>,
+    pub(crate) comment: Option<Vec<u8>>,
+    pub(crate) header_crc: bool,
+}
+
+impl GzipHeader {
+    /// Returns whether the `FTEXT` flag was set, indicating the content is probably text.
+    pub fn is_text(&self) -> bool {
+        self.text
+    }
+
+    /// Returns the modification time of the original file in seconds since the Unix epoch, `0`
+    /// means no time is available.
+    pub fn mtime(&self) -> u32 {
+        self.mtime
+    }
+
+    /// Returns the `XFL` byte, which describes the compression level used.
+    pub fn extra_flags(&self) -> u8 {
+        self.extra_flags
+    }
+
+    /// Returns the `OS` byte, identifying the filesystem the member was compressed on, `255`
+    /// means unknown.
+    pub fn operating_system(&self) -> u8 {
+        self.operating_system
+    }
+
+    /// Returns the raw contents of the `FEXTRA` field, if present.
+    pub fn extra(&self) -> Option<&[u8]> {
+        self.extra.as_deref()
+    }
+
+    /// Returns the original filename from the `FNAME` field, if present, without the trailing
+    /// zero byte.
+    pub fn filename(&self) -> Option<&[u8]> {
+        self.filename.as_deref()
+    }
+
+    /// Returns the `FCOMMENT` field, if present, without the trailing zero byte.
+    pub fn comment(&self) -> Option<&[u8]> {
+        self.comment.as_deref()
+    }
+
+    /// Returns whether the header is protected by a `FHCRC` checksum.
+    pub fn has_header_crc(&self) -> bool {
+        self.header_crc
+    }
+}
+
+impl Default for GzipHeader {
+    fn default() -> Self {
+        Self {
+            text: false,
+            mtime: 0,
+            extra_flags: 0,
+            operating_system: 255,
+            extra: None,
+            filename: None,
+            comment: None,
+            header_crc: false,
+        }
+    }
+}
+
+/// A builder for the [`GzipHeader`] written by the gzip encoders.
+///
+/// ```
+/// use async_compression::GzipHeaderBuilder;
+///
+/// let header = GzipHeaderBuilder::new()
+///     .filename("report.csv")
+///     .mtime(1_600_000_000)
+///     .comment("nightly export")
+///     .extra_subfield(*b"AP", b"\x01")
+///     .operating_system(3)
+///     .header_crc(true)
+///     .build();
+/// assert_eq!(header.filename(), Some(&b"report.csv"[..]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GzipHeaderBuilder {
+    header: GzipHeader,
+}
+
+impl GzipHeaderBuilder {
+    /// Creates a builder for a header with no optional fields, a zero mtime and an unknown OS.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `FTEXT` flag, indicating the content is probably text.
+    pub fn text(mut self, text: bool) -> Self {
+        self.header.text = text;
+        self
+    }
+
+    /// Sets the modification time of the original file in seconds since the Unix epoch.
+    pub fn mtime(mut self, mtime: u32) -> Self {
+        self.header.mtime = mtime;
+        self
+    }
+
+    /// Sets the `OS` byte, e.g. `3` for Unix, see RFC 1952 for the full list.
+    pub fn operating_system(mut self, operating_system: u8) -> Self {
+        self.header.operating_system = operating_system;
+        self
+    }
+
+    /// Sets the original filename, stored in the `FNAME` field.
+    ///
+    /// # Panics
+    ///
+    /// If `filename` contains a zero byte.
+    pub fn filename(mut self, filename: impl Into<Vec<u8>>) -> Self {
+        let filename = filename.into();
+        assert!(!filename.contains(&0), "gzip filename contains a zero byte");
+        self.header.filename = Some(filename);
+        self
+    }
+
+    /// Sets the `FCOMMENT` field.
+    ///
+    /// # Panics
+    ///
+    /// If `comment` contains a zero byte.
+    pub fn comment(mut self, comment: impl Into<Vec<u8>>) -> Self {
+        let comment = comment.into();
+        assert!(!comment.contains(&0), "gzip comment contains a zero byte");
+        self.header.comment = Some(comment);
+        self
+    }
+
+    /// Appends a subfield with the two byte identifier `id` to the `FEXTRA` field.
+    ///
+    /// # Panics
+    ///
+    /// If the `FEXTRA` field would grow larger than 65535 bytes.
+    pub fn extra_subfield(mut self, id: [u8; 2], data: &[u8]) -> Self {
+        let extra = self.header.extra.get_or_insert_with(Vec::new);
+        assert!(
+            extra.len() + 4 + data.len() <= usize::from(u16::MAX),
+            "gzip extra field is too long"
+        );
+        extra.extend_from_slice(&id);
+        extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        extra.extend_from_slice(data);
+        self
+    }
+
+    /// Sets whether the header is protected by a `FHCRC` checksum.
+    pub fn header_crc(mut self, enabled: bool) -> Self {
+        self.header.header_crc = enabled;
+        self
+    }
+
+    /// Returns the built header.
+    pub fn build(self) -> GzipHeader {
+        self.header
+    }
+}
@@ -0,0 +1,101 @@
+use lz4::liblz4::{
+    BlockChecksum, BlockMode, BlockSize, ContentChecksum, FrameType, LZ4FFrameInfo, LZ4FPreferences,
+};
+
+/// Maximum size of the independently compressed blocks within an LZ4 frame.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lz4BlockSize {
+    /// Blocks of up to 64 KiB, the default.
+    Max64KB,
+    /// Blocks of up to 256 KiB.
+    Max256KB,
+    /// Blocks of up to 1 MiB.
+    Max1MB,
+    /// Blocks of up to 4 MiB.
+    Max4MB,
+}
+
+/// Frame options for the LZ4 encoders.
+///
+/// The defaults match `liblz4`: 64 KiB linked blocks with no checksums.
+///
+/// ```
+/// use async_compression::{Lz4BlockSize, Lz4Params};
+///
+/// let params = Lz4Params::default()
+///     .block_size(Lz4BlockSize::Max4MB)
+///     .block_independence(true)
+///     .content_checksum(true);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lz4Params {
+    block_size: Option<Lz4BlockSize>,
+    block_independence: bool,
+    content_checksum: bool,
+    block_checksum: bool,
+}
+
+impl Lz4Params {
+    /// Sets the maximum size of each block.
+    pub fn block_size(mut self, block_size: Lz4BlockSize) -> Self {
+        self.block_size = Some(block_size);
+        self
+    }
+
+    /// Sets whether blocks are compressed independently of each other, by default each block can
+    /// reference data from the previous blocks which improves the compression ratio but means
+    /// the frame can only be decoded sequentially.
+    pub fn block_independence(mut self, enabled: bool) -> Self {
+        self.block_independence = enabled;
+        self
+    }
+
+    /// Sets whether a checksum of the uncompressed content is appended to the frame.
+    pub fn content_checksum(mut self, enabled: bool) -> Self {
+        self.content_checksum = enabled;
+        self
+    }
+
+    /// Sets whether a checksum of the compressed data is appended to each block.
+    pub fn block_checksum(mut self, enabled: bool) -> Self {
+        self.block_checksum = enabled;
+        self
+    }
+
+    pub(crate) fn into_preferences(self, compression_level: u32) -> LZ4FPreferences {
+        LZ4FPreferences {
+            frame_info: LZ4FFrameInfo {
+                block_size_id: match self.block_size {
+                    None => BlockSize::Default,
+                    Some(Lz4BlockSize::Max64KB) => BlockSize::Max64KB,
+                    Some(Lz4BlockSize::Max256KB) => BlockSize::Max256KB,
+                    Some(Lz4BlockSize::Max1MB) => BlockSize::Max1MB,
+                    Some(Lz4BlockSize::Max4MB) => BlockSize::Max4MB,
+                },
+                block_mode: if self.block_independence {
+                    BlockMode::Independent
+                } else {
+                    BlockMode::Linked
+                },
+                content_checksum_flag: if self.content_checksum {
+                    ContentChecksum::ChecksumEnabled
+                } else {
+                    ContentChecksum::NoChecksum
+                },
+                frame_type: FrameType::Frame,
+                content_size: 0,
+                dict_id: 0,
+                block_checksum_flag: if self.block_checksum {
+                    BlockChecksum::BlockChecksumEnabled
+                } else {
+                    BlockChecksum::NoBlockChecksum
+                },
+            },
+            compression_level,
+            auto_flush: 0,
+            favor_dec_speed: 0,
+            reserved: [0; 3],
+        }
+    }
+}
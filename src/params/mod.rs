@@ -0,0 +1,29 @@
+//! Typed configuration and metadata for the algorithms, these are re-exported from the crate root.
+
+mod algorithm;
+#[cfg(feature = "brotli")]
+mod brotli;
+#[cfg(any(feature = "deflate", feature = "gzip", feature = "zlib"))]
+mod flate;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "lz4")]
+mod lz4;
+#[cfg(feature = "xz")]
+mod xz;
+#[cfg(feature = "zstd")]
+mod zstd;
+
+pub use self::algorithm::Algorithm;
+#[cfg(feature = "brotli")]
+pub use self::brotli::{BrotliMode, BrotliParams};
+#[cfg(any(feature = "deflate", feature = "gzip", feature = "zlib"))]
+pub use self::flate::{FlateParams, FlateStrategy};
+#[cfg(feature = "gzip")]
+pub use self::gzip::{GzipHeader, GzipHeaderBuilder};
+#[cfg(feature = "lz4")]
+pub use self::lz4::{Lz4BlockSize, Lz4Params};
+#[cfg(feature = "xz")]
+pub use self::xz::{XzCheck, XzFilter, XzMode, XzParams};
+#[cfg(feature = "zstd")]
+pub use self::zstd::{ZstdDecoderDictionary, ZstdEncoderDictionary, ZstdParams, ZstdStrategy};
@@ -0,0 +1,297 @@
+use crate::codec::lzma_raw::{preset_options, Stream};
+use lzma_sys::{
+    lzma_easy_encoder, lzma_filter, lzma_mt, lzma_stream_encoder, lzma_stream_encoder_mt, lzma_vli,
+    LZMA_CHECK_CRC32, LZMA_CHECK_CRC64, LZMA_CHECK_NONE, LZMA_CHECK_SHA256, LZMA_FILTER_ARM,
+    LZMA_FILTER_ARMTHUMB, LZMA_FILTER_IA64, LZMA_FILTER_LZMA2, LZMA_FILTER_POWERPC,
+    LZMA_FILTER_SPARC, LZMA_FILTER_X86, LZMA_MODE_FAST, LZMA_MODE_NORMAL, LZMA_VLI_UNKNOWN,
+};
+use std::{io::Result, mem, os::raw::c_void, ptr};
+
+/// How long in milliseconds the multithreaded encoder may wait on its workers in one call before
+/// returning with no progress, so it never holds up the async task for long.
+const MT_TIMEOUT: u32 = 1;
+
+// The delta filter from liblzma's `lzma/delta.h`, which lzma-sys doesn't export
+const LZMA_FILTER_DELTA: lzma_vli = 0x03;
+const LZMA_DELTA_TYPE_BYTE: u32 = 0;
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct lzma_options_delta {
+    type_: u32,
+    dist: u32,
+    reserved_int: [u32; 8],
+    reserved_ptr: [*mut c_void; 2],
+}
+
+/// The integrity check stored for each block of an xz stream.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XzCheck {
+    /// No integrity check.
+    None,
+    /// CRC32.
+    Crc32,
+    /// CRC64, the default.
+    Crc64,
+    /// SHA-256.
+    Sha256,
+}
+
+/// A filter applied to the data before LZMA2 compression.
+///
+/// Apart from `Delta` these are branch/call/jump (BCJ) filters, which convert the relative
+/// addresses in executable code for one architecture to absolute ones so repeated calls compress
+/// better.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XzFilter {
+    /// x86 and x86-64 code.
+    X86,
+    /// Big endian PowerPC code.
+    PowerPc,
+    /// Itanium code.
+    Ia64,
+    /// 32-bit ARM code.
+    Arm,
+    /// ARM Thumb code.
+    ArmThumb,
+    /// SPARC code.
+    Sparc,
+    /// Replaces each byte with its difference from the byte `distance` bytes before it, from 1
+    /// to 256. Suits data made of fixed size samples such as uncompressed audio or images, e.g. a
+    /// `distance` of 4 for 16-bit stereo audio.
+    Delta {
+        /// The distance in bytes to the byte each one is compared with.
+        distance: u32,
+    },
+}
+
+/// How thoroughly the LZMA2 encoder analyzes the matches it finds.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XzMode {
+    /// Used by the levels up to 3.
+    Fast,
+    /// Used by the higher levels.
+    Normal,
+}
+
+/// Options for the xz encoders.
+///
+/// By default data is compressed on the calling thread into a single block, with the LZMA2
+/// options of the compression level and a CRC64 check.
+///
+/// ```
+/// use async_compression::{XzCheck, XzFilter, XzParams};
+///
+/// let params = XzParams::default()
+///     .check(XzCheck::Sha256)
+///     .filter(XzFilter::X86)
+///     .dict_size(16 << 20);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct XzParams {
+    threads: Option<u32>,
+    block_size: Option<u64>,
+    check: Option<XzCheck>,
+    filters: Vec<XzFilter>,
+    dict_size: Option<u32>,
+    literal_context_bits: Option<u32>,
+    literal_position_bits: Option<u32>,
+    position_bits: Option<u32>,
+    nice_len: Option<u32>,
+    mode: Option<XzMode>,
+}
+
+impl XzParams {
+    /// Sets the number of worker threads used to compress, using liblzma's multithreaded encoder.
+    /// The input is split into independent blocks which are compressed in parallel, this also
+    /// allows other tools to decompress the output in parallel.
+    ///
+    /// Compressing only hands input over to the workers and output is produced once their blocks
+    /// complete, so it may lag behind the input. While waiting on the workers the calling task
+    /// yields and is woken to check on them again, rather than blocking its thread. Flushing
+    /// waits until the workers are done with all of the input so far and also ends the current
+    /// block.
+    pub fn threads(mut self, threads: u32) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Sets the maximum uncompressed size of each block when compressing on multiple threads, by
+    /// default liblzma uses 3 times the dictionary size or 1 MiB, whichever is more. Each worker
+    /// allocates about 3 times this much memory.
+    ///
+    /// This only has an effect with [`threads`](Self::threads) set.
+    pub fn block_size(mut self, size: u64) -> Self {
+        self.block_size = Some(size);
+        self
+    }
+
+    /// Sets the integrity check stored for each block.
+    pub fn check(mut self, check: XzCheck) -> Self {
+        self.check = Some(check);
+        self
+    }
+
+    /// Appends a filter to the chain applied before LZMA2 compression, at most 3 can be used.
+    ///
+    /// The filters don't support flushing within a block, so with any set flushing ends the
+    /// current block.
+    pub fn filter(mut self, filter: XzFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Sets the LZMA2 dictionary size in bytes, from 4 KiB to 1.5 GiB. Decoders need this much
+    /// memory to decompress the data.
+    pub fn dict_size(mut self, size: u32) -> Self {
+        self.dict_size = Some(size);
+        self
+    }
+
+    /// Sets the number of literal context bits (`lc`), from 0 to 4 with `lc + lp` at most 4.
+    pub fn literal_context_bits(mut self, bits: u32) -> Self {
+        self.literal_context_bits = Some(bits);
+        self
+    }
+
+    /// Sets the number of literal position bits (`lp`), from 0 to 4 with `lc + lp` at most 4.
+    pub fn literal_position_bits(mut self, bits: u32) -> Self {
+        self.literal_position_bits = Some(bits);
+        self
+    }
+
+    /// Sets the number of position bits (`pb`), from 0 to 4.
+    pub fn position_bits(mut self, bits: u32) -> Self {
+        self.position_bits = Some(bits);
+        self
+    }
+
+    /// Sets the length of a match that is considered good enough to stop searching, from 2 to
+    /// 273.
+    pub fn nice_len(mut self, len: u32) -> Self {
+        self.nice_len = Some(len);
+        self
+    }
+
+    /// Sets the LZMA2 compression mode.
+    pub fn mode(mut self, mode: XzMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Whether the encoder supports `SyncFlush`, otherwise flushing has to end the block.
+    pub(crate) fn supports_sync_flush(&self) -> bool {
+        self.threads.is_none() && self.filters.is_empty()
+    }
+
+    pub(crate) fn into_encoder(self, preset: u32) -> Result<Stream> {
+        let check = match self.check.unwrap_or(XzCheck::Crc64) {
+            XzCheck::None => LZMA_CHECK_NONE,
+            XzCheck::Crc32 => LZMA_CHECK_CRC32,
+            XzCheck::Crc64 => LZMA_CHECK_CRC64,
+            XzCheck::Sha256 => LZMA_CHECK_SHA256,
+        };
+
+        let mut options = preset_options(preset)?;
+        let mut custom = !self.filters.is_empty();
+
+        if let Some(size) = self.dict_size {
+            options.dict_size = size;
+            custom = true;
+        }
+        if let Some(bits) = self.literal_context_bits {
+            options.lc = bits;
+            custom = true;
+        }
+        if let Some(bits) = self.literal_position_bits {
+            options.lp = bits;
+            custom = true;
+        }
+        if let Some(bits) = self.position_bits {
+            options.pb = bits;
+            custom = true;
+        }
+        if let Some(len) = self.nice_len {
+            options.nice_len = len;
+            custom = true;
+        }
+        if let Some(mode) = self.mode {
+            options.mode = match mode {
+                XzMode::Fast => LZMA_MODE_FAST,
+                XzMode::Normal => LZMA_MODE_NORMAL,
+            };
+            custom = true;
+        }
+
+        // The filter options are only read during init, they just need to outlive the chain
+        let mut deltas: Vec<lzma_options_delta> = self
+            .filters
+            .iter()
+            .filter_map(|filter| match *filter {
+                XzFilter::Delta { distance } => Some(lzma_options_delta {
+                    type_: LZMA_DELTA_TYPE_BYTE,
+                    dist: distance,
+                    reserved_int: [0; 8],
+                    reserved_ptr: [ptr::null_mut(); 2],
+                }),
+                _ => None,
+            })
+            .collect();
+        let mut deltas = deltas.iter_mut();
+
+        let mut filters: Vec<lzma_filter> = self
+            .filters
+            .iter()
+            .map(|filter| {
+                let id = match filter {
+                    XzFilter::X86 => LZMA_FILTER_X86,
+                    XzFilter::PowerPc => LZMA_FILTER_POWERPC,
+                    XzFilter::Ia64 => LZMA_FILTER_IA64,
+                    XzFilter::Arm => LZMA_FILTER_ARM,
+                    XzFilter::ArmThumb => LZMA_FILTER_ARMTHUMB,
+                    XzFilter::Sparc => LZMA_FILTER_SPARC,
+                    XzFilter::Delta { .. } => {
+                        let options = deltas.next().unwrap();
+                        return lzma_filter {
+                            id: LZMA_FILTER_DELTA,
+                            options: options as *mut lzma_options_delta as *mut c_void,
+                        };
+                    }
+                };
+                lzma_filter {
+                    id,
+                    options: ptr::null_mut(),
+                }
+            })
+            .collect();
+        filters.push(lzma_filter {
+            id: LZMA_FILTER_LZMA2,
+            options: &mut options as *mut _ as *mut c_void,
+        });
+        filters.push(lzma_filter {
+            id: LZMA_VLI_UNKNOWN,
+            options: ptr::null_mut(),
+        });
+
+        if let Some(threads) = self.threads {
+            // Safety: this is a plain C struct, zeroed is a valid value for all of its fields
+            let mut mt: lzma_mt = unsafe { mem::zeroed() };
+            mt.threads = threads;
+            mt.timeout = MT_TIMEOUT;
+            mt.block_size = self.block_size.unwrap_or(0);
+            mt.preset = preset;
+            mt.check = check;
+            if custom {
+                mt.filters = filters.as_ptr();
+            }
+            Stream::new(|stream| unsafe { lzma_stream_encoder_mt(stream, &mt) })
+        } else if custom {
+            Stream::new(|stream| unsafe { lzma_stream_encoder(stream, filters.as_ptr(), check) })
+        } else {
+            Stream::new(|stream| unsafe { lzma_easy_encoder(stream, preset, check) })
+        }
+    }
+}
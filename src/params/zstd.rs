@@ -0,0 +1,267 @@
+use core::fmt;
+use std::sync::Arc;
+
+use libzstd::dict::{DecoderDictionary, EncoderDictionary};
+
+/// Keeps a copy of the dictionary alive for as long as the digested form borrowing it.
+struct Owned<T> {
+    // Declared first so it is dropped before the data it borrows.
+    dictionary: T,
+    _data: Box<[u8]>,
+}
+
+impl<T> Owned<T> {
+    fn new(data: &[u8], digest: impl FnOnce(&'static [u8]) -> T) -> Self {
+        let data: Box<[u8]> = data.into();
+        // SAFETY: the boxed slice is never mutated or moved out of its allocation, and is only
+        // freed after `dictionary` has been dropped.
+        let borrowed = unsafe { &*(&*data as *const [u8]) };
+        Self {
+            dictionary: digest(borrowed),
+            _data: data,
+        }
+    }
+}
+
+/// A zstd dictionary digested once for compression at a fixed level, for use with the
+/// `with_prepared_dictionary` constructor of the zstd encoders.
+///
+/// Digesting a dictionary is much more expensive than compressing a small message, so when
+/// compressing many messages with the same dictionary this should be created once and cloned,
+/// clones are cheap and share the digested dictionary.
+///
+/// ```
+/// use async_compression::{Level, ZstdEncoderDictionary};
+///
+/// # let dictionary = &[];
+/// let dictionary = ZstdEncoderDictionary::new(dictionary, Level::Default);
+/// ```
+#[derive(Clone)]
+pub struct ZstdEncoderDictionary {
+    inner: Arc<Owned<EncoderDictionary<'static>>>,
+}
+
+impl ZstdEncoderDictionary {
+    /// Digests the raw `dictionary` for compressing at `level`, the dictionary is copied so does
+    /// not need to outlive this value.
+    pub fn new(dictionary: &[u8], level: crate::Level) -> Self {
+        let level = level.into_zstd();
+        Self {
+            inner: Arc::new(Owned::new(dictionary, |data| {
+                EncoderDictionary::new(data, level)
+            })),
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &EncoderDictionary<'static> {
+        &self.inner.dictionary
+    }
+}
+
+impl fmt::Debug for ZstdEncoderDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdEncoderDictionary").finish()
+    }
+}
+
+/// A zstd dictionary digested once for decompression, for use with the
+/// `with_prepared_dictionary` constructor of the zstd decoders.
+///
+/// As with [`ZstdEncoderDictionary`] this should be created once and cloned when decompressing
+/// many messages with the same dictionary.
+#[derive(Clone)]
+pub struct ZstdDecoderDictionary {
+    inner: Arc<Owned<DecoderDictionary<'static>>>,
+}
+
+impl ZstdDecoderDictionary {
+    /// Digests the raw `dictionary`, the dictionary is copied so does not need to outlive this
+    /// value.
+    pub fn new(dictionary: &[u8]) -> Self {
+        Self {
+            inner: Arc::new(Owned::new(dictionary, DecoderDictionary::new)),
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &DecoderDictionary<'static> {
+        &self.inner.dictionary
+    }
+}
+
+impl fmt::Debug for ZstdDecoderDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDecoderDictionary").finish()
+    }
+}
+
+/// The match finding strategy used by the zstd encoders, in order of increasing compression ratio
+/// and decreasing speed.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZstdStrategy {
+    /// `ZSTD_fast`, used by the lowest levels.
+    Fast,
+    /// `ZSTD_dfast`, a double hash table variant of `Fast`.
+    Dfast,
+    /// `ZSTD_greedy`.
+    Greedy,
+    /// `ZSTD_lazy`.
+    Lazy,
+    /// `ZSTD_lazy2`.
+    Lazy2,
+    /// `ZSTD_btlazy2`, using a binary tree match finder.
+    Btlazy2,
+    /// `ZSTD_btopt`, using optimal parsing.
+    Btopt,
+    /// `ZSTD_btultra`.
+    Btultra,
+    /// `ZSTD_btultra2`, used by the highest levels.
+    Btultra2,
+}
+
+/// Advanced compression options for the zstd encoders, options left unset use the value chosen
+/// by the compression level.
+///
+/// ```
+/// use async_compression::ZstdParams;
+///
+/// let params = ZstdParams::default()
+///     .window_log(27)
+///     .long_distance_matching(true)
+///     .checksum(true);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ZstdParams {
+    window_log: Option<u32>,
+    long_distance_matching: Option<bool>,
+    strategy: Option<ZstdStrategy>,
+    checksum: Option<bool>,
+    content_size: Option<bool>,
+    dictionary_id: Option<bool>,
+    #[cfg(feature = "zstdmt")]
+    nb_workers: Option<u32>,
+    #[cfg(feature = "zstdmt")]
+    job_size: Option<u32>,
+}
+
+impl ZstdParams {
+    /// Sets the base 2 logarithm of the maximum back-reference distance (`windowLog`), from 10 to
+    /// 31 (30 on 32-bit platforms).
+    ///
+    /// Decoders need that much memory to decompress the data, by default they reject frames with
+    /// a window larger than 128 MiB (a log of 27), see the zstd decoder's `window_log_max`.
+    pub fn window_log(mut self, log: u32) -> Self {
+        self.window_log = Some(log);
+        self
+    }
+
+    /// Sets whether long distance matching is used, which finds matches far back in large inputs.
+    /// This increases the window log to 27 unless set explicitly.
+    pub fn long_distance_matching(mut self, enabled: bool) -> Self {
+        self.long_distance_matching = Some(enabled);
+        self
+    }
+
+    /// Sets the match finding strategy.
+    pub fn strategy(mut self, strategy: ZstdStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Sets whether a checksum of the uncompressed content is appended to each frame, disabled by
+    /// default.
+    pub fn checksum(mut self, enabled: bool) -> Self {
+        self.checksum = Some(enabled);
+        self
+    }
+
+    /// Sets whether the uncompressed size is written to the frame header when it is known in
+    /// advance, enabled by default. The streaming encoders don't know the size in advance, so
+    /// frames they produce never contain it.
+    pub fn content_size(mut self, enabled: bool) -> Self {
+        self.content_size = Some(enabled);
+        self
+    }
+
+    /// Sets whether the ID of the dictionary used is written to the frame header, enabled by
+    /// default.
+    pub fn dictionary_id(mut self, enabled: bool) -> Self {
+        self.dictionary_id = Some(enabled);
+        self
+    }
+
+    /// Sets the number of worker threads used to compress (`nbWorkers`), 0 (the default)
+    /// compresses on the calling thread.
+    ///
+    /// With workers, compressing only hands input over to them and output is produced once their
+    /// jobs complete, so it may lag behind the input. libzstd blocks while waiting on them, so the
+    /// encoder is then driven from a thread of its own and the calling task is woken once it
+    /// completes, rather than being blocked. Flushing waits until the workers are done with all of
+    /// the input so far.
+    #[cfg(feature = "zstdmt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstdmt")))]
+    pub fn nb_workers(mut self, workers: u32) -> Self {
+        self.nb_workers = Some(workers);
+        self
+    }
+
+    /// Sets the size in bytes of the input compressed by each worker job (`jobSize`), values
+    /// below 512 KiB are raised to it. The default of 0 chooses it based on the window log.
+    ///
+    /// This only has an effect with [`nb_workers`](Self::nb_workers) set.
+    #[cfg(feature = "zstdmt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstdmt")))]
+    pub fn job_size(mut self, size: u32) -> Self {
+        self.job_size = Some(size);
+        self
+    }
+
+    /// Whether compression happens on worker threads rather than the calling thread.
+    #[cfg(feature = "zstdmt")]
+    pub(crate) fn uses_workers(&self) -> bool {
+        matches!(self.nb_workers, Some(workers) if workers > 0)
+    }
+
+    pub(crate) fn apply(self, encoder: &mut libzstd::stream::raw::Encoder) -> std::io::Result<()> {
+        use zstd_safe::{CParameter, Strategy};
+
+        if let Some(log) = self.window_log {
+            encoder.set_parameter(CParameter::WindowLog(log))?;
+        }
+        if let Some(enabled) = self.long_distance_matching {
+            encoder.set_parameter(CParameter::EnableLongDistanceMatching(enabled))?;
+        }
+        if let Some(strategy) = self.strategy {
+            encoder.set_parameter(CParameter::Strategy(match strategy {
+                ZstdStrategy::Fast => Strategy::ZSTD_fast,
+                ZstdStrategy::Dfast => Strategy::ZSTD_dfast,
+                ZstdStrategy::Greedy => Strategy::ZSTD_greedy,
+                ZstdStrategy::Lazy => Strategy::ZSTD_lazy,
+                ZstdStrategy::Lazy2 => Strategy::ZSTD_lazy2,
+                ZstdStrategy::Btlazy2 => Strategy::ZSTD_btlazy2,
+                ZstdStrategy::Btopt => Strategy::ZSTD_btopt,
+                ZstdStrategy::Btultra => Strategy::ZSTD_btultra,
+                ZstdStrategy::Btultra2 => Strategy::ZSTD_btultra2,
+            }))?;
+        }
+        if let Some(enabled) = self.checksum {
+            encoder.set_parameter(CParameter::ChecksumFlag(enabled))?;
+        }
+        if let Some(enabled) = self.content_size {
+            encoder.set_parameter(CParameter::ContentSizeFlag(enabled))?;
+        }
+        if let Some(enabled) = self.dictionary_id {
+            encoder.set_parameter(CParameter::DictIdFlag(enabled))?;
+        }
+        #[cfg(feature = "zstdmt")]
+        {
+            if let Some(workers) = self.nb_workers {
+                encoder.set_parameter(CParameter::NbWorkers(workers))?;
+            }
+            if let Some(size) = self.job_size {
+                encoder.set_parameter(CParameter::JobSize(size))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,7 +4,10 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{
+    codec::{Decode, Limited},
+    util::PartialBuffer,
+};
 use bytes::{Buf, Bytes, BytesMut};
 use futures_core::{ready, stream::Stream};
 use pin_project_lite::pin_project;
@@ -21,49 +24,109 @@ enum State {
 }
 
 pin_project! {
+    /// A generic decoder which reads compressed data from an underlying
+    /// [`Stream`](futures_core::stream::Stream) and decompresses it with any [`Decode`]
+    /// implementation.
+    ///
+    /// This structure implements a [`Stream`](futures_core::stream::Stream) interface and will emit
+    /// a stream of uncompressed data.
     #[derive(Debug)]
     pub struct Decoder<S, D: Decode> {
         #[pin]
         stream: S,
-        decoder: D,
+        decoder: Limited<D>,
         state: State,
         input: Bytes,
         output: BytesMut,
+        buffer_size: usize,
         multiple_members: bool,
     }
 }
 
 impl<S: Stream<Item = Result<Bytes>>, D: Decode> Decoder<S, D> {
+    /// Creates a new decoder which will read compressed data from the given stream and decompress it
+    /// with `decoder`.
     pub fn new(stream: S, decoder: D) -> Self {
         Self {
             stream,
-            decoder,
+            decoder: Limited::new(decoder),
             state: State::Reading,
             input: Bytes::new(),
             output: BytesMut::new(),
+            buffer_size: OUTPUT_BUFFER_SIZE,
             multiple_members: false,
         }
     }
 
+    /// Acquires a reference to the underlying stream that this decoder is wrapping.
     pub fn get_ref(&self) -> &S {
         &self.stream
     }
 
+    /// Acquires a mutable reference to the underlying stream that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the stream which may
+    /// otherwise confuse this decoder.
     pub fn get_mut(&mut self) -> &mut S {
         &mut self.stream
     }
 
+    /// Acquires a pinned mutable reference to the underlying stream that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the stream which may
+    /// otherwise confuse this decoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
         self.project().stream
     }
 
+    /// Consumes this decoder returning the underlying stream.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> S {
         self.stream
     }
 
+    pub(crate) fn get_decoder_ref(&self) -> &D {
+        self.decoder.get_ref()
+    }
+
+    pub(crate) fn get_decoder_mut(&mut self) -> &mut D {
+        self.decoder.get_mut()
+    }
+
+    /// Sets how many bytes the output buffer is grown by each time the codec needs more space.
+    /// The default is currently 8 KB, but may change in the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn buffer_size(&mut self, size: usize) {
+        assert!(size > 0, "buffer size must be non-zero");
+        self.buffer_size = size;
+    }
+
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either the end of the wrapped
+    /// stream or another compressed member/frame to follow.
     pub fn multiple_members(&mut self, enabled: bool) {
         self.multiple_members = enabled;
     }
+
+    /// Limits the total number of bytes this decoder will output, exceeding it fails with a
+    /// [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no limit
+    /// by default.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.decoder.max_output_size(limit);
+    }
+
+    /// Limits the ratio between the number of bytes output and compressed bytes read by this
+    /// decoder, exceeding it fails with a [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio)
+    /// error. This is checked continuously while decoding, not just at the end of the stream.
+    /// There is no limit by default.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.decoder.max_ratio(limit);
+    }
 }
 
 impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
@@ -72,18 +135,19 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
         let this = self.project();
 
-        let (mut stream, input, state, decoder, multiple_members) = (
+        let (mut stream, input, state, decoder, multiple_members, buffer_size) = (
             this.stream,
             this.input,
             this.state,
             this.decoder,
             *this.multiple_members,
+            *this.buffer_size,
         );
 
         let mut output = PartialBuffer::new(this.output);
 
         let result = (|| loop {
-            let output_capacity = output.written().len() + OUTPUT_BUFFER_SIZE;
+            let output_capacity = output.written().len() + buffer_size;
             output.get_mut().resize(output_capacity, 0);
 
             *state = match state {
@@ -118,6 +182,12 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
                 State::Flushing => {
                     if decoder.finish(&mut output)? {
                         if multiple_members {
+                            // Don't mix output from separate members, so that any per-member
+                            // state exposed by the decoder matches the data last returned.
+                            if !output.written().is_empty() {
+                                *state = State::Next;
+                                return Poll::Ready(None);
+                            }
                             State::Next
                         } else {
                             State::Done
@@ -20,6 +20,12 @@ enum State {
 }
 
 pin_project! {
+    /// A generic encoder which reads uncompressed data from an underlying
+    /// [`Stream`](futures_core::stream::Stream) and compresses it with any [`Encode`]
+    /// implementation.
+    ///
+    /// This structure implements a [`Stream`](futures_core::stream::Stream) interface and will emit
+    /// a stream of compressed data.
     #[derive(Debug)]
     pub struct Encoder<S, E: Encode> {
         #[pin]
@@ -28,35 +34,63 @@ pin_project! {
         state: State,
         input: Bytes,
         output: BytesMut,
+        buffer_size: usize,
     }
 }
 
 impl<S: Stream<Item = Result<Bytes>>, E: Encode> Encoder<S, E> {
-    pub(crate) fn new(stream: S, encoder: E) -> Self {
+    /// Creates a new encoder which will read uncompressed data from the given stream and compress it
+    /// with `encoder`.
+    pub fn new(stream: S, encoder: E) -> Self {
         Self {
             stream,
             encoder,
             state: State::Reading,
             input: Bytes::new(),
             output: BytesMut::new(),
+            buffer_size: OUTPUT_BUFFER_SIZE,
         }
     }
 
-    pub(crate) fn get_ref(&self) -> &S {
+    /// Acquires a reference to the underlying stream that this encoder is wrapping.
+    pub fn get_ref(&self) -> &S {
         &self.stream
     }
 
-    pub(crate) fn get_mut(&mut self) -> &mut S {
+    /// Acquires a mutable reference to the underlying stream that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the stream which may
+    /// otherwise confuse this encoder.
+    pub fn get_mut(&mut self) -> &mut S {
         &mut self.stream
     }
 
-    pub(crate) fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
+    /// Acquires a pinned mutable reference to the underlying stream that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the stream which may
+    /// otherwise confuse this encoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
         self.project().stream
     }
 
-    pub(crate) fn into_inner(self) -> S {
+    /// Consumes this encoder returning the underlying stream.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called.
+    pub fn into_inner(self) -> S {
         self.stream
     }
+
+    /// Sets how many bytes the output buffer is grown by each time the codec needs more space.
+    /// The default is currently 8 KB, but may change in the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn buffer_size(&mut self, size: usize) {
+        assert!(size > 0, "buffer size must be non-zero");
+        self.buffer_size = size;
+    }
 }
 
 impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
@@ -65,13 +99,18 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
         let this = self.project();
 
-        let (mut stream, input, state, encoder) =
-            (this.stream, this.input, this.state, this.encoder);
+        let (mut stream, input, state, encoder, buffer_size) = (
+            this.stream,
+            this.input,
+            this.state,
+            this.encoder,
+            *this.buffer_size,
+        );
 
         let mut output = PartialBuffer::new(this.output);
 
         let result = (|| loop {
-            let output_capacity = output.written().len() + OUTPUT_BUFFER_SIZE;
+            let output_capacity = output.written().len() + buffer_size;
             output.get_mut().resize(output_capacity, 0);
 
             *state = match *state {
@@ -111,6 +150,10 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
                     return Poll::Ready(None);
                 }
             };
+
+            if encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
         })();
 
         match result {
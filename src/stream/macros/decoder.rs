@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident { $($constructor:tt)* } $({ $($method:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -15,14 +15,8 @@ macro_rules! decoder {
         impl<S: futures_core::stream::Stream<Item = std::io::Result<bytes::Bytes>>> $name<S> {
             /// Creates a new decoder which will read compressed data from the given stream and
             /// emit an uncompressed stream.
-            pub fn new(stream: S) -> Self {
-                Self {
-                    inner: crate::stream::Decoder::new(
-                        stream,
-                        crate::codec::$name::new(),
-                    ),
-                }
-            }
+            ///
+            $($constructor)*
 
             /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
             /// when reaching the end of a compressed member/frame and expect either the end of the
@@ -31,6 +25,32 @@ macro_rules! decoder {
                 self.inner.multiple_members(enabled);
             }
 
+            /// Limits the total number of bytes this decoder will output, exceeding it fails with
+            /// a [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no
+            /// limit by default.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits the ratio between the number of bytes output and compressed bytes read by
+            /// this decoder, exceeding it fails with a
+            /// [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio) error. This is checked
+            /// continuously while decoding, not just at the end of the stream. There is no limit
+            /// by default.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
+            /// Sets how many bytes the output buffer is grown by each time the codec needs more
+            /// space. The default is currently 8 KB, but may change in the future.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `size` is zero.
+            pub fn buffer_size(&mut self, size: usize) {
+                self.inner.buffer_size(size);
+            }
+
             /// Acquires a reference to the underlying stream that this decoder is wrapping.
             pub fn get_ref(&self) -> &S {
                 self.inner.get_ref()
@@ -61,6 +81,8 @@ macro_rules! decoder {
             pub fn into_inner(self) -> S {
                 self.inner.into_inner()
             }
+
+            $($($method)*)*
         }
 
         impl<S: futures_core::stream::Stream<Item = std::io::Result<bytes::Bytes>>>
@@ -20,6 +20,16 @@ macro_rules! encoder {
                 $($constructor)*
             )*
 
+            /// Sets how many bytes the output buffer is grown by each time the codec needs more
+            /// space. The default is currently 8 KB, but may change in the future.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `size` is zero.
+            pub fn buffer_size(&mut self, size: usize) {
+                self.inner.buffer_size(size);
+            }
+
             /// Acquires a reference to the underlying stream that this encoder is wrapping.
             pub fn get_ref(&self) -> &$inner {
                 self.inner.get_ref()
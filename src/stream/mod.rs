@@ -12,6 +12,6 @@
 mod macros;
 mod generic;
 
-pub(crate) use self::generic::{Decoder, Encoder};
+pub use self::generic::{Decoder, Encoder};
 
 algos!(stream<S>);
@@ -0,0 +1,113 @@
+macro_rules! decoder {
+    ($(#[$attr:meta])* $name:ident { $($constructor:tt)* } $({ $($method:tt)* })*) => {
+        pin_project_lite::pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            ///
+            /// This structure implements an [`AsyncRead`](tokio::io::AsyncRead) interface and will
+            /// read compressed data from an underlying stream and emit a stream of uncompressed data.
+            pub struct $name<R> {
+                #[pin]
+                inner: crate::tokio::bufread::Decoder<R, crate::codec::$name>,
+            }
+        }
+
+        impl<R: tokio::io::AsyncBufRead> $name<R> {
+            /// Creates a new decoder which will read compressed data from the given stream and
+            /// emit a uncompressed stream.
+            ///
+            $($constructor)*
+
+            /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
+            /// when reaching the end of a compressed member/frame and expect either EOF or another
+            /// compressed member/frame to follow it in the stream.
+            pub fn multiple_members(&mut self, enabled: bool) {
+                self.inner.multiple_members(enabled);
+            }
+
+            /// Configure stopping at trailing data when decoding multiple members/frames, if
+            /// enabled the data following the end of each member/frame is checked for the
+            /// signature of the format and the decoder stops cleanly if it doesn't match, leaving
+            /// the trailing data unread in the underlying reader. Otherwise that data is decoded as
+            /// another member/frame, likely failing with an error.
+            ///
+            /// The check only looks at the data currently buffered by the underlying reader, if
+            /// that is shorter than the signature and matches as far as it goes it is taken to be
+            /// another member/frame. Formats without a signature (brotli, deflate, lzma and raw
+            /// LZMA2) never stop early.
+            pub fn stop_at_trailing_data(&mut self, enabled: bool) {
+                self.inner.stop_at_trailing_data(enabled);
+            }
+
+            /// Limits the total number of bytes this decoder will output, exceeding it fails with
+            /// a [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no
+            /// limit by default.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits the ratio between the number of bytes output and compressed bytes read by
+            /// this decoder, exceeding it fails with a
+            /// [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio) error. This is checked
+            /// continuously while decoding, not just at the end of the stream. There is no limit
+            /// by default.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
+            /// Acquires a reference to the underlying reader that this decoder is wrapping.
+            pub fn get_ref(&self) -> &R {
+                self.inner.get_ref()
+            }
+
+            /// Acquires a mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_mut(&mut self) -> &mut R {
+                self.inner.get_mut()
+            }
+
+            /// Acquires a pinned mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_pin_mut(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut R> {
+                self.project().inner.get_pin_mut()
+            }
+
+            /// Consumes this decoder returning the underlying reader.
+            ///
+            /// Note that this may discard internal state of this decoder, so care should be taken
+            /// to avoid losing resources when this is called.
+            pub fn into_inner(self) -> R {
+                self.inner.into_inner()
+            }
+
+            $($($method)*)*
+        }
+
+        impl<R: tokio::io::AsyncBufRead> tokio::io::AsyncRead for $name<R> {
+            fn poll_read(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &mut tokio::io::ReadBuf<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                self.project().inner.poll_read(cx, buf)
+            }
+        }
+
+        const _: () = {
+            fn _assert() {
+                use crate::util::{_assert_send, _assert_sync};
+                use core::pin::Pin;
+                use tokio::io::AsyncBufRead;
+
+                _assert_send::<$name<Pin<Box<dyn AsyncBufRead + Send>>>>();
+                _assert_sync::<$name<Pin<Box<dyn AsyncBufRead + Sync>>>>();
+            }
+        };
+    }
+}
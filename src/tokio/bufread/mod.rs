@@ -0,0 +1,21 @@
+//! Types which operate over [`AsyncBufRead`](tokio::io::AsyncBufRead) streams, both encoders and
+//! decoders for various formats.
+//!
+//! These types have no internal buffers of their own, they decode or encode directly from the
+//! buffer of the wrapped reader into the buffer passed to each read. To control the buffer sizes
+//! wrap the underlying reader in a `BufReader` with the desired capacity and read with a buffer of
+//! the desired size.
+//!
+//! The decoders read exactly the compressed data from the underlying reader and no more, once the
+//! end of the compressed stream is reached any data following it is left unread and can be read
+//! from the reader returned by `get_mut` or `into_inner`. With `multiple_members` enabled the data
+//! following each member/frame is decoded as another member/frame, unless
+//! `stop_at_trailing_data` is also enabled and it doesn't look like one.
+
+#[macro_use]
+mod macros;
+mod generic;
+
+pub use generic::{Decoder, Encoder};
+
+algos!(tokio::bufread<R>);
@@ -0,0 +1,4 @@
+//! Implementations for IO traits exported by [`tokio` v1.x](::tokio).
+
+pub mod bufread;
+pub mod write;
@@ -0,0 +1,287 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    codec::{Decode, Limited},
+    tokio::write::{AsyncBufWrite, BufWriter},
+    util::PartialBuffer,
+};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use tokio::io::AsyncWrite;
+
+#[derive(Debug)]
+enum State {
+    Decoding,
+    Finishing,
+    Done,
+    Next,
+}
+
+pin_project! {
+    /// A generic decoder which takes in compressed data, decompresses it with any [`Decode`]
+    /// implementation and writes it to an underlying [`AsyncWrite`](tokio::io::AsyncWrite).
+    #[derive(Debug)]
+    pub struct Decoder<W, D: Decode> {
+        #[pin]
+        writer: BufWriter<W>,
+        decoder: Limited<D>,
+        state: State,
+        multiple_members: bool,
+        trailing: Option<Vec<u8>>,
+    }
+}
+
+impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
+    /// Creates a new decoder which will take in compressed data, decompress it with `decoder` and write
+    /// it to the given stream.
+    pub fn new(writer: W, decoder: D) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            decoder: Limited::new(decoder),
+            state: State::Decoding,
+            multiple_members: false,
+            trailing: None,
+        }
+    }
+
+    /// Acquires a reference to the underlying writer that this decoder is wrapping.
+    pub fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying writer that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this decoder.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this decoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().writer.get_pin_mut()
+    }
+
+    /// Consumes this decoder returning the underlying writer.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken to avoid
+    /// losing resources when this is called.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    /// Sets the size of the buffer holding output until it is written to the underlying writer.
+    /// The default is currently 8 KB, but may change in the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn buffer_size(&mut self, size: usize) {
+        assert!(size > 0, "buffer size must be non-zero");
+        self.writer.set_capacity(size);
+    }
+
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either the end of the input or
+    /// another compressed member/frame to be written after it.
+    pub fn multiple_members(&mut self, enabled: bool) {
+        self.multiple_members = enabled;
+    }
+
+    /// Configure handling of data written after the end of the compressed stream, by default it
+    /// is rejected with an [`InvalidData`](std::io::ErrorKind::InvalidData) error. If enabled it
+    /// is instead accepted and kept to be retrieved with [`trailing_data`](Self::trailing_data).
+    ///
+    /// All of the trailing data is kept in memory, so this should only be enabled when the amount
+    /// written is otherwise bounded.
+    pub fn collect_trailing_data(&mut self, enabled: bool) {
+        self.trailing = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    /// Returns the data written after the end of the compressed stream, always empty unless
+    /// enabled with [`collect_trailing_data`](Self::collect_trailing_data).
+    pub fn trailing_data(&self) -> &[u8] {
+        self.trailing.as_deref().unwrap_or_default()
+    }
+
+    /// Limits the total number of bytes this decoder will output, exceeding it fails with a
+    /// [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no limit
+    /// by default.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.decoder.max_output_size(limit);
+    }
+
+    /// Limits the ratio between the number of bytes output and compressed bytes read by this
+    /// decoder, exceeding it fails with a [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio)
+    /// error. This is checked continuously while decoding, not just at the end of the stream.
+    /// There is no limit by default.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.decoder.max_ratio(limit);
+    }
+
+    pub(crate) fn get_decoder_ref(&self) -> &D {
+        self.decoder.get_ref()
+    }
+
+    pub(crate) fn get_decoder_mut(&mut self) -> &mut D {
+        self.decoder.get_mut()
+    }
+
+    fn do_poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        input: &mut PartialBuffer<&[u8]>,
+    ) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        loop {
+            let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
+            let mut output = PartialBuffer::new(output);
+
+            *this.state = match this.state {
+                State::Decoding => {
+                    if this.decoder.decode(input, &mut output)? {
+                        State::Finishing
+                    } else {
+                        State::Decoding
+                    }
+                }
+
+                State::Finishing => {
+                    if this.decoder.finish(&mut output)? {
+                        if *this.multiple_members {
+                            this.decoder.reinit()?;
+                            State::Next
+                        } else {
+                            State::Done
+                        }
+                    } else {
+                        State::Finishing
+                    }
+                }
+
+                State::Done => {
+                    return match this.trailing {
+                        Some(trailing) => {
+                            trailing.extend_from_slice(input.unwritten());
+                            input.advance(input.unwritten().len());
+                            Poll::Ready(Ok(()))
+                        }
+                        None => Poll::Ready(Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "trailing data after the end of the stream",
+                        ))),
+                    };
+                }
+
+                State::Next => {
+                    if input.unwritten().is_empty() {
+                        State::Next
+                    } else {
+                        State::Decoding
+                    }
+                }
+            };
+
+            let produced = output.written().len();
+            this.writer.as_mut().produce(produced);
+
+            if input.unwritten().is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+
+    fn do_poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        loop {
+            let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
+            let mut output = PartialBuffer::new(output);
+
+            let (state, done) = match this.state {
+                State::Decoding => {
+                    let done = this.decoder.flush(&mut output)?;
+                    (State::Decoding, done)
+                }
+
+                State::Finishing => {
+                    if this.decoder.finish(&mut output)? {
+                        if *this.multiple_members {
+                            this.decoder.reinit()?;
+                            (State::Next, false)
+                        } else {
+                            (State::Done, false)
+                        }
+                    } else {
+                        (State::Finishing, false)
+                    }
+                }
+
+                State::Done => (State::Done, true),
+
+                State::Next => (State::Next, true),
+            };
+
+            *this.state = state;
+
+            let produced = output.written().len();
+            this.writer.as_mut().produce(produced);
+
+            if done {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+impl<W: AsyncWrite, D: Decode> AsyncWrite for Decoder<W, D> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let mut input = PartialBuffer::new(buf);
+
+        match self.do_poll_write(cx, &mut input)? {
+            Poll::Pending if input.written().is_empty() => Poll::Pending,
+            _ => Poll::Ready(Ok(input.written().len())),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.as_mut().do_poll_flush(cx))?;
+        ready!(self.project().writer.as_mut().poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if let State::Decoding = self.as_mut().project().state {
+            *self.as_mut().project().state = State::Finishing;
+        }
+
+        ready!(self.as_mut().do_poll_flush(cx))?;
+
+        // The end of the input after a complete member/frame is the end of the stream.
+        if let State::Next = self.as_mut().project().state {
+            *self.as_mut().project().state = State::Done;
+        }
+
+        if let State::Done = self.as_mut().project().state {
+            ready!(self.as_mut().project().writer.as_mut().poll_shutdown(cx))?;
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Ready(Err(Error::new(
+                ErrorKind::Other,
+                "Attempt to shutdown before finishing input",
+            )))
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    codec::Encode,
+    tokio::write::{AsyncBufWrite, BufWriter},
+    util::PartialBuffer,
+};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use tokio::io::AsyncWrite;
+
+#[derive(Debug)]
+enum State {
+    Encoding,
+    Finishing,
+    Done,
+}
+
+pin_project! {
+    /// A generic encoder which takes in uncompressed data, compresses it with any [`Encode`]
+    /// implementation and writes it to an underlying [`AsyncWrite`](tokio::io::AsyncWrite).
+    #[derive(Debug)]
+    pub struct Encoder<W, E: Encode> {
+        #[pin]
+        writer: BufWriter<W>,
+        encoder: E,
+        state: State,
+    }
+}
+
+impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
+    /// Creates a new encoder which will take in uncompressed data, compress it with `encoder` and write
+    /// it to the given stream.
+    pub fn new(writer: W, encoder: E) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            encoder,
+            state: State::Encoding,
+        }
+    }
+
+    /// Acquires a reference to the underlying writer that this encoder is wrapping.
+    pub fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
+        self.project().writer.get_pin_mut()
+    }
+
+    /// Consumes this encoder returning the underlying writer.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    /// Sets the size of the buffer holding output until it is written to the underlying writer.
+    /// The default is currently 8 KB, but may change in the future.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn buffer_size(&mut self, size: usize) {
+        assert!(size > 0, "buffer size must be non-zero");
+        self.writer.set_capacity(size);
+    }
+
+    fn do_poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        input: &mut PartialBuffer<&[u8]>,
+    ) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        loop {
+            let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
+            let mut output = PartialBuffer::new(output);
+
+            *this.state = match this.state {
+                State::Encoding => {
+                    this.encoder.encode(input, &mut output)?;
+                    State::Encoding
+                }
+
+                State::Finishing | State::Done => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Other, "Write after shutdown")))
+                }
+            };
+
+            let produced = output.written().len();
+            this.writer.as_mut().produce(produced);
+
+            if input.unwritten().is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    fn do_poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        loop {
+            let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
+            let mut output = PartialBuffer::new(output);
+
+            let done = match this.state {
+                State::Encoding => this.encoder.flush(&mut output)?,
+
+                State::Finishing | State::Done => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Other, "Flush after shutdown")))
+                }
+            };
+
+            let produced = output.written().len();
+            this.writer.as_mut().produce(produced);
+
+            if done {
+                return Poll::Ready(Ok(()));
+            }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    fn do_poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        loop {
+            let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
+            let mut output = PartialBuffer::new(output);
+
+            *this.state = match this.state {
+                State::Encoding | State::Finishing => {
+                    if this.encoder.finish(&mut output)? {
+                        State::Done
+                    } else {
+                        State::Finishing
+                    }
+                }
+
+                State::Done => State::Done,
+            };
+
+            let produced = output.written().len();
+            this.writer.as_mut().produce(produced);
+
+            if let State::Done = this.state {
+                return Poll::Ready(Ok(()));
+            }
+            if this.encoder.poll_wait(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<W: AsyncWrite, E: Encode> AsyncWrite for Encoder<W, E> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let mut input = PartialBuffer::new(buf);
+
+        match self.do_poll_write(cx, &mut input)? {
+            Poll::Pending if input.written().is_empty() => Poll::Pending,
+            _ => Poll::Ready(Ok(input.written().len())),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.as_mut().do_poll_flush(cx))?;
+        ready!(self.project().writer.as_mut().poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        ready!(self.as_mut().do_poll_shutdown(cx))?;
+        ready!(self.project().writer.as_mut().poll_shutdown(cx))?;
+        Poll::Ready(Ok(()))
+    }
+}
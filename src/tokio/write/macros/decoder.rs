@@ -0,0 +1,140 @@
+macro_rules! decoder {
+    ($(#[$attr:meta])* $name:ident { $($constructor:tt)* } $({ $($method:tt)* })*) => {
+        pin_project_lite::pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            ///
+            /// This structure implements an [`AsyncWrite`](tokio::io::AsyncWrite) interface and will
+            /// take in compressed data and write it uncompressed to an underlying stream.
+            pub struct $name<W> {
+                #[pin]
+                inner: crate::tokio::write::Decoder<W, crate::codec::$name>,
+            }
+        }
+
+        impl<W: tokio::io::AsyncWrite> $name<W> {
+            /// Creates a new decoder which will take in compressed data and write it uncompressedd
+            /// to the given stream.
+            ///
+            $($constructor)*
+
+            /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
+            /// when reaching the end of a compressed member/frame and expect either the end of the
+            /// input or another compressed member/frame to be written after it.
+            pub fn multiple_members(&mut self, enabled: bool) {
+                self.inner.multiple_members(enabled);
+            }
+
+            /// Configure handling of data written after the end of the compressed stream, by
+            /// default it is rejected with an [`InvalidData`](std::io::ErrorKind::InvalidData)
+            /// error. If enabled it is instead accepted and kept to be retrieved with
+            /// [`trailing_data`](Self::trailing_data).
+            ///
+            /// All of the trailing data is kept in memory, so this should only be enabled when the
+            /// amount written is otherwise bounded.
+            pub fn collect_trailing_data(&mut self, enabled: bool) {
+                self.inner.collect_trailing_data(enabled);
+            }
+
+            /// Returns the data written after the end of the compressed stream, always empty
+            /// unless enabled with [`collect_trailing_data`](Self::collect_trailing_data).
+            pub fn trailing_data(&self) -> &[u8] {
+                self.inner.trailing_data()
+            }
+
+            /// Limits the total number of bytes this decoder will output, exceeding it fails with
+            /// a [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no
+            /// limit by default.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits the ratio between the number of bytes output and compressed bytes read by
+            /// this decoder, exceeding it fails with a
+            /// [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio) error. This is checked
+            /// continuously while decoding, not just at the end of the stream. There is no limit
+            /// by default.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
+            /// Sets the size of the buffer holding output until it is written to the underlying
+            /// writer. The default is currently 8 KB, but may change in the future.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `size` is zero.
+            pub fn buffer_size(&mut self, size: usize) {
+                self.inner.buffer_size(size);
+            }
+
+            /// Acquires a reference to the underlying reader that this decoder is wrapping.
+            pub fn get_ref(&self) -> &W {
+                self.inner.get_ref()
+            }
+
+            /// Acquires a mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_mut(&mut self) -> &mut W {
+                self.inner.get_mut()
+            }
+
+            /// Acquires a pinned mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_pin_mut(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut W> {
+                self.project().inner.get_pin_mut()
+            }
+
+            /// Consumes this decoder returning the underlying reader.
+            ///
+            /// Note that this may discard internal state of this decoder, so care should be taken
+            /// to avoid losing resources when this is called.
+            pub fn into_inner(self) -> W {
+                self.inner.into_inner()
+            }
+
+            $($($method)*)*
+        }
+
+        impl<W: tokio::io::AsyncWrite> tokio::io::AsyncWrite for $name<W> {
+            fn poll_write(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &[u8],
+            ) -> std::task::Poll<std::io::Result<usize>> {
+                self.project().inner.poll_write(cx, buf)
+            }
+
+            fn poll_flush(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                self.project().inner.poll_flush(cx)
+            }
+
+            fn poll_shutdown(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                self.project().inner.poll_shutdown(cx)
+            }
+        }
+
+        const _: () = {
+            fn _assert() {
+                use crate::util::{_assert_send, _assert_sync};
+                use core::pin::Pin;
+                use tokio::io::AsyncWrite;
+
+                _assert_send::<$name<Pin<Box<dyn AsyncWrite + Send>>>>();
+                _assert_sync::<$name<Pin<Box<dyn AsyncWrite + Sync>>>>();
+            }
+        };
+    }
+}
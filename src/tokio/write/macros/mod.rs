@@ -0,0 +1,4 @@
+#[macro_use]
+mod decoder;
+#[macro_use]
+mod encoder;
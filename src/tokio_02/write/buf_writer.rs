@@ -42,6 +42,17 @@ impl<W: AsyncWrite> BufWriter<W> {
         }
     }
 
+    /// Changes the buffer capacity, any data still buffered is kept so the new buffer may end up
+    /// larger than `cap`.
+    pub fn set_capacity(&mut self, cap: usize) {
+        let pending = &self.buf[self.written..self.buffered];
+        let mut buf = vec![0; cap.max(pending.len())].into_boxed_slice();
+        buf[..pending.len()].copy_from_slice(pending);
+        self.buffered = pending.len();
+        self.written = 0;
+        self.buf = buf;
+    }
+
     fn partial_flush_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         let mut this = self.project();
 
@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident { $($constructor:tt)* } $({ $($method:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -15,10 +15,57 @@ macro_rules! decoder {
         impl<W: tokio_02::io::AsyncWrite> $name<W> {
             /// Creates a new decoder which will take in compressed data and write it uncompressedd
             /// to the given stream.
-            pub fn new(read: W) -> $name<W> {
-                $name {
-                    inner: crate::tokio_02::write::Decoder::new(read, crate::codec::$name::new()),
-                }
+            ///
+            $($constructor)*
+
+            /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
+            /// when reaching the end of a compressed member/frame and expect either the end of the
+            /// input or another compressed member/frame to be written after it.
+            pub fn multiple_members(&mut self, enabled: bool) {
+                self.inner.multiple_members(enabled);
+            }
+
+            /// Configure handling of data written after the end of the compressed stream, by
+            /// default it is rejected with an [`InvalidData`](std::io::ErrorKind::InvalidData)
+            /// error. If enabled it is instead accepted and kept to be retrieved with
+            /// [`trailing_data`](Self::trailing_data).
+            ///
+            /// All of the trailing data is kept in memory, so this should only be enabled when the
+            /// amount written is otherwise bounded.
+            pub fn collect_trailing_data(&mut self, enabled: bool) {
+                self.inner.collect_trailing_data(enabled);
+            }
+
+            /// Returns the data written after the end of the compressed stream, always empty
+            /// unless enabled with [`collect_trailing_data`](Self::collect_trailing_data).
+            pub fn trailing_data(&self) -> &[u8] {
+                self.inner.trailing_data()
+            }
+
+            /// Limits the total number of bytes this decoder will output, exceeding it fails with
+            /// a [`LimitExceeded::OutputSize`](crate::LimitExceeded::OutputSize) error. There is no
+            /// limit by default.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits the ratio between the number of bytes output and compressed bytes read by
+            /// this decoder, exceeding it fails with a
+            /// [`LimitExceeded::Ratio`](crate::LimitExceeded::Ratio) error. This is checked
+            /// continuously while decoding, not just at the end of the stream. There is no limit
+            /// by default.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
+            /// Sets the size of the buffer holding output until it is written to the underlying
+            /// writer. The default is currently 8 KB, but may change in the future.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `size` is zero.
+            pub fn buffer_size(&mut self, size: usize) {
+                self.inner.buffer_size(size);
             }
 
             /// Acquires a reference to the underlying reader that this decoder is wrapping.
@@ -51,6 +98,8 @@ macro_rules! decoder {
             pub fn into_inner(self) -> W {
                 self.inner.into_inner()
             }
+
+            $($($method)*)*
         }
 
         impl<W: tokio_02::io::AsyncWrite> tokio_02::io::AsyncWrite for $name<W> {
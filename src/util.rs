@@ -1,6 +1,14 @@
-pub fn _assert_send<T: Send>() {}
-pub fn _assert_sync<T: Sync>() {}
+//! Utilities for implementing the [`codec`](crate::codec) traits.
 
+pub(crate) fn _assert_send<T: Send>() {}
+pub(crate) fn _assert_sync<T: Sync>() {}
+
+/// A buffer paired with a cursor splitting it into a written (or consumed) prefix and an unwritten
+/// (or unconsumed) suffix.
+///
+/// This is used for both the input and output of the [`codec`](crate::codec) traits, for input the
+/// "written" part is the data that has been consumed, for output it is the data that has been
+/// produced.
 #[derive(Debug, Default)]
 pub struct PartialBuffer<B: AsRef<[u8]>> {
     buffer: B,
@@ -8,37 +16,48 @@ pub struct PartialBuffer<B: AsRef<[u8]>> {
 }
 
 impl<B: AsRef<[u8]>> PartialBuffer<B> {
-    pub(crate) fn new(buffer: B) -> Self {
+    /// Wraps `buffer` with the cursor at the start.
+    pub fn new(buffer: B) -> Self {
         Self { buffer, index: 0 }
     }
 
-    pub(crate) fn written(&self) -> &[u8] {
+    /// Returns the part of the buffer before the cursor.
+    pub fn written(&self) -> &[u8] {
         &self.buffer.as_ref()[..self.index]
     }
 
-    pub(crate) fn unwritten(&self) -> &[u8] {
+    /// Returns the part of the buffer after the cursor.
+    pub fn unwritten(&self) -> &[u8] {
         &self.buffer.as_ref()[self.index..]
     }
 
-    pub(crate) fn advance(&mut self, amount: usize) {
+    /// Moves the cursor forward by `amount` bytes.
+    ///
+    /// The cursor must not be moved past the end of the buffer.
+    pub fn advance(&mut self, amount: usize) {
         self.index += amount;
     }
 
-    pub(crate) fn get_mut(&mut self) -> &mut B {
+    /// Acquires a mutable reference to the underlying buffer.
+    pub fn get_mut(&mut self) -> &mut B {
         &mut self.buffer
     }
 
-    pub(crate) fn into_inner(self) -> B {
+    /// Consumes this wrapper returning the underlying buffer.
+    pub fn into_inner(self) -> B {
         self.buffer
     }
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> PartialBuffer<B> {
-    pub(crate) fn unwritten_mut(&mut self) -> &mut [u8] {
+    /// Returns the part of the buffer after the cursor mutably.
+    pub fn unwritten_mut(&mut self) -> &mut [u8] {
         &mut self.buffer.as_mut()[self.index..]
     }
 
-    pub(crate) fn copy_unwritten_from<C: AsRef<[u8]>>(&mut self, other: &mut PartialBuffer<C>) {
+    /// Copies as much data as fits from the unwritten part of `other` into the unwritten part of
+    /// this buffer, advancing both cursors.
+    pub fn copy_unwritten_from<C: AsRef<[u8]>>(&mut self, other: &mut PartialBuffer<C>) {
         let len = std::cmp::min(self.unwritten().len(), other.unwritten().len());
 
         self.unwritten_mut()[..len].copy_from_slice(&other.unwritten()[..len]);
@@ -49,7 +68,8 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> PartialBuffer<B> {
 }
 
 impl<B: AsRef<[u8]> + Default> PartialBuffer<B> {
-    pub(crate) fn take(&mut self) -> Self {
+    /// Takes the current buffer and cursor out, leaving an empty default buffer in its place.
+    pub fn take(&mut self) -> Self {
         std::mem::replace(self, Self::new(B::default()))
     }
 }
@@ -0,0 +1,247 @@
+//! Tests running every enabled algorithm through the runtime selected `AnyEncoder` and
+//! `AnyDecoder` types.
+
+#[allow(unused)]
+use async_compression::Algorithm;
+#[allow(unused)]
+use std::iter::FromIterator;
+
+#[allow(unused)]
+mod utils;
+
+#[allow(unused)]
+type Codec = (Algorithm, fn(&[u8]) -> Vec<u8>, fn(&[u8]) -> Vec<u8>);
+
+/// Every enabled algorithm, along with synchronous functions to compress and decompress it.
+#[allow(unused)]
+const ALGORITHMS: &[Codec] = &[
+    #[cfg(feature = "brotli")]
+    (
+        Algorithm::Brotli,
+        utils::brotli::sync::compress,
+        utils::brotli::sync::decompress,
+    ),
+    #[cfg(feature = "bzip2")]
+    (
+        Algorithm::Bzip2,
+        utils::bzip2::sync::compress,
+        utils::bzip2::sync::decompress,
+    ),
+    #[cfg(feature = "deflate")]
+    (
+        Algorithm::Deflate,
+        utils::deflate::sync::compress,
+        utils::deflate::sync::decompress,
+    ),
+    #[cfg(feature = "gzip")]
+    (
+        Algorithm::Gzip,
+        utils::gzip::sync::compress,
+        utils::gzip::sync::decompress,
+    ),
+    #[cfg(feature = "lz4")]
+    (
+        Algorithm::Lz4,
+        utils::lz4::sync::compress,
+        utils::lz4::sync::decompress,
+    ),
+    #[cfg(feature = "lzip")]
+    (
+        Algorithm::Lzip,
+        utils::lzip::sync::compress,
+        utils::lzip::sync::decompress,
+    ),
+    #[cfg(feature = "lzma")]
+    (
+        Algorithm::Lzma,
+        utils::lzma::sync::compress,
+        utils::lzma::sync::decompress,
+    ),
+    #[cfg(feature = "lzma2")]
+    (
+        Algorithm::Lzma2,
+        utils::lzma2::sync::compress,
+        utils::lzma2::sync::decompress,
+    ),
+    #[cfg(feature = "xz")]
+    (
+        Algorithm::Xz,
+        utils::xz::sync::compress,
+        utils::xz::sync::decompress,
+    ),
+    #[cfg(feature = "zlib")]
+    (
+        Algorithm::Zlib,
+        utils::zlib::sync::compress,
+        utils::zlib::sync::decompress,
+    ),
+    #[cfg(feature = "zstd")]
+    (
+        Algorithm::Zstd,
+        utils::zstd::sync::compress,
+        utils::zstd::sync::decompress,
+    ),
+];
+
+#[allow(unused)]
+fn input() -> Vec<u8> {
+    Vec::from_iter((0..20_000).map(|i| (i % 251) as u8))
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "stream")]
+fn stream_any() {
+    use async_compression::stream::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = utils::InputStream::from(vec![input()]);
+
+        let encoder = AnyEncoder::with_quality(input.stream(), algorithm, Level::Fastest);
+        let compressed = stream_to_vec(encoder);
+        assert_eq!(decompress(&compressed), input.bytes(), "{:?}", algorithm);
+
+        let compressed = utils::InputStream::from(vec![compress(&input.bytes())]);
+        let output = stream_to_vec(AnyDecoder::new(compressed.stream(), algorithm));
+        assert_eq!(output, input.bytes(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn futures_bufread_any() {
+    use async_compression::futures::bufread::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = utils::InputStream::from(vec![input()]);
+
+        let encoder = AnyEncoder::with_quality(input.reader(), algorithm, Level::Fastest);
+        let compressed = async_read_to_vec(encoder);
+        assert_eq!(decompress(&compressed), input.bytes(), "{:?}", algorithm);
+
+        let compressed = utils::InputStream::from(vec![compress(&input.bytes())]);
+        let output = async_read_to_vec(AnyDecoder::new(compressed.reader(), algorithm));
+        assert_eq!(output, input.bytes(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_any() {
+    use async_compression::futures::write::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = vec![input()];
+
+        let compressed = async_write_to_vec(
+            &input,
+            |output| Box::pin(AnyEncoder::with_quality(output, algorithm, Level::Fastest)),
+            65_536,
+        );
+        assert_eq!(decompress(&compressed), input[0], "{:?}", algorithm);
+
+        let output = async_write_to_vec(
+            &[compress(&input[0])],
+            |output| Box::pin(AnyDecoder::new(output, algorithm)),
+            65_536,
+        );
+        assert_eq!(output, input[0], "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_bufread_any() {
+    use async_compression::tokio_02::bufread::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = utils::InputStream::from(vec![input()]);
+
+        let encoder = AnyEncoder::with_quality(input.tokio_02_reader(), algorithm, Level::Fastest);
+        let compressed = tokio_02_read_to_vec(encoder);
+        assert_eq!(decompress(&compressed), input.bytes(), "{:?}", algorithm);
+
+        let compressed = utils::InputStream::from(vec![compress(&input.bytes())]);
+        let output = tokio_02_read_to_vec(AnyDecoder::new(compressed.tokio_02_reader(), algorithm));
+        assert_eq!(output, input.bytes(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_write_any() {
+    use async_compression::tokio_02::write::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = vec![input()];
+
+        let compressed = tokio_02_write_to_vec(
+            &input,
+            |output| Box::pin(AnyEncoder::with_quality(output, algorithm, Level::Fastest)),
+            65_536,
+        );
+        assert_eq!(decompress(&compressed), input[0], "{:?}", algorithm);
+
+        let output = tokio_02_write_to_vec(
+            &[compress(&input[0])],
+            |output| Box::pin(AnyDecoder::new(output, algorithm)),
+            65_536,
+        );
+        assert_eq!(output, input[0], "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio")]
+fn tokio_bufread_any() {
+    use async_compression::tokio::bufread::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = utils::InputStream::from(vec![input()]);
+
+        let encoder = AnyEncoder::with_quality(input.tokio_reader(), algorithm, Level::Fastest);
+        let compressed = tokio_read_to_vec(encoder);
+        assert_eq!(decompress(&compressed), input.bytes(), "{:?}", algorithm);
+
+        let compressed = utils::InputStream::from(vec![compress(&input.bytes())]);
+        let output = tokio_read_to_vec(AnyDecoder::new(compressed.tokio_reader(), algorithm));
+        assert_eq!(output, input.bytes(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio")]
+fn tokio_write_any() {
+    use async_compression::tokio::write::{AnyDecoder, AnyEncoder};
+    use utils::prelude::*;
+
+    for &(algorithm, compress, decompress) in ALGORITHMS {
+        let input = vec![input()];
+
+        let compressed = tokio_write_to_vec(
+            &input,
+            |output| Box::pin(AnyEncoder::with_quality(output, algorithm, Level::Fastest)),
+            65_536,
+        );
+        assert_eq!(decompress(&compressed), input[0], "{:?}", algorithm);
+
+        let output = tokio_write_to_vec(
+            &[compress(&input[0])],
+            |output| Box::pin(AnyDecoder::new(output, algorithm)),
+            65_536,
+        );
+        assert_eq!(output, input[0], "{:?}", algorithm);
+    }
+}
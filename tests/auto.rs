@@ -0,0 +1,258 @@
+//! Tests detecting the algorithm of compressed data with `AutoDecoder`.
+
+#[allow(unused)]
+use async_compression::Algorithm;
+#[allow(unused)]
+use std::iter::FromIterator;
+
+#[allow(unused)]
+mod utils;
+
+#[allow(unused)]
+type Codec = (Algorithm, fn(&[u8]) -> Vec<u8>);
+
+/// Every enabled algorithm that can be detected, along with a synchronous function to compress
+/// it.
+#[allow(unused)]
+const ALGORITHMS: &[Codec] = &[
+    #[cfg(feature = "bzip2")]
+    (Algorithm::Bzip2, utils::bzip2::sync::compress),
+    #[cfg(feature = "gzip")]
+    (Algorithm::Gzip, utils::gzip::sync::compress),
+    #[cfg(feature = "lz4")]
+    (Algorithm::Lz4, utils::lz4::sync::compress),
+    #[cfg(feature = "lzip")]
+    (Algorithm::Lzip, utils::lzip::sync::compress),
+    #[cfg(feature = "lzma")]
+    (Algorithm::Lzma, utils::lzma::sync::compress),
+    #[cfg(feature = "xz")]
+    (Algorithm::Xz, utils::xz::sync::compress),
+    #[cfg(feature = "zlib")]
+    (Algorithm::Zlib, utils::zlib::sync::compress),
+    #[cfg(feature = "zstd")]
+    (Algorithm::Zstd, utils::zstd::sync::compress),
+];
+
+#[allow(unused)]
+fn input() -> Vec<u8> {
+    Vec::from_iter((0..20_000).map(|i| (i % 251) as u8))
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "stream")]
+fn stream_auto() {
+    use async_compression::stream::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+        let compressed = compress(&input);
+        // Split inside the signature to check detection waits for enough data
+        let compressed = utils::InputStream::from(vec![
+            compressed[..1].to_vec(),
+            compressed[1..3].to_vec(),
+            compressed[3..].to_vec(),
+        ]);
+
+        let decoder = AutoDecoder::new(compressed.stream());
+        pin_mut!(decoder);
+        assert_eq!(decoder.algorithm(), None);
+        let output: Vec<u8> = block_on_stream(decoder.as_mut())
+            .flat_map(Result::unwrap)
+            .collect();
+        assert_eq!(output, input, "{:?}", algorithm);
+        assert_eq!(decoder.algorithm(), Some(algorithm));
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_auto_passthrough() {
+    use async_compression::stream::AutoDecoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![b"pla".to_vec(), b"in text".to_vec()]);
+    let mut decoder = AutoDecoder::new(input.stream());
+    decoder.passthrough(true);
+    pin_mut!(decoder);
+    let output: Vec<u8> = block_on_stream(decoder.as_mut())
+        .flat_map(Result::unwrap)
+        .collect();
+    assert_eq!(output, b"plain text");
+    assert_eq!(decoder.algorithm(), None);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_auto_passthrough_empty() {
+    use async_compression::stream::AutoDecoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![]);
+    let mut decoder = AutoDecoder::new(input.stream());
+    decoder.passthrough(true);
+    assert!(stream_to_vec(decoder).is_empty());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_auto_unrecognized() {
+    use async_compression::stream::AutoDecoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![b"plain text".to_vec()]);
+    let decoder = AutoDecoder::new(input.stream());
+    pin_mut!(decoder);
+    let err = block_on_stream(decoder).find_map(Result::err).unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn futures_bufread_auto() {
+    use async_compression::futures::bufread::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+        let compressed = utils::InputStream::from(vec![compress(&input)]);
+
+        let decoder = AutoDecoder::new(compressed.reader());
+        pin_mut!(decoder);
+        let mut output = Vec::new();
+        block_on(futures::io::AsyncReadExt::read_to_end(
+            &mut decoder,
+            &mut output,
+        ))
+        .unwrap();
+        assert_eq!(output, input, "{:?}", algorithm);
+        assert_eq!(decoder.algorithm(), Some(algorithm));
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_bufread_auto_passthrough() {
+    use async_compression::futures::bufread::AutoDecoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![b"plain text".to_vec()]);
+    let mut decoder = AutoDecoder::new(input.reader());
+    decoder.passthrough(true);
+    assert_eq!(async_read_to_vec(decoder), b"plain text");
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_auto() {
+    use async_compression::futures::write::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+        let compressed = compress(&input);
+
+        let output = async_write_to_vec(
+            &[compressed[..2].to_vec(), compressed[2..].to_vec()],
+            |output| Box::pin(AutoDecoder::new(output)),
+            65_536,
+        );
+        assert_eq!(output, input, "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_auto_passthrough() {
+    use async_compression::futures::write::AutoDecoder;
+    use utils::prelude::*;
+
+    let output = async_write_to_vec(
+        &[b"plain text".to_vec()],
+        |output| {
+            let mut decoder = AutoDecoder::new(output);
+            decoder.passthrough(true);
+            Box::pin(decoder)
+        },
+        2,
+    );
+    assert_eq!(output, b"plain text");
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_bufread_auto() {
+    use async_compression::tokio_02::bufread::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+        let compressed = utils::InputStream::from(vec![compress(&input)]);
+
+        let output = tokio_02_read_to_vec(AutoDecoder::new(compressed.tokio_02_reader()));
+        assert_eq!(output, input, "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_write_auto() {
+    use async_compression::tokio_02::write::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+
+        let output = tokio_02_write_to_vec(
+            &[compress(&input)],
+            |output| Box::pin(AutoDecoder::new(output)),
+            65_536,
+        );
+        assert_eq!(output, input, "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio")]
+fn tokio_bufread_auto() {
+    use async_compression::tokio::bufread::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+        let compressed = utils::InputStream::from(vec![compress(&input)]);
+
+        let output = tokio_read_to_vec(AutoDecoder::new(compressed.tokio_reader()));
+        assert_eq!(output, input, "{:?}", algorithm);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "tokio")]
+fn tokio_write_auto() {
+    use async_compression::tokio::write::AutoDecoder;
+    use utils::prelude::*;
+
+    for &(algorithm, compress) in ALGORITHMS {
+        let input = input();
+
+        let output = tokio_write_to_vec(
+            &[compress(&input)],
+            |output| Box::pin(AutoDecoder::new(output)),
+            65_536,
+        );
+        assert_eq!(output, input, "{:?}", algorithm);
+    }
+}
@@ -2,3 +2,54 @@
 mod utils;
 
 test_cases!(brotli);
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn brotli_stream_params() {
+    use async_compression::{BrotliMode, BrotliParams, Level};
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![b"hello world, hello world".to_vec()]);
+
+    let params = BrotliParams::default()
+        .window_bits(24)
+        .mode(BrotliMode::Text)
+        .size_hint(input.len());
+    let encoder = utils::brotli::stream::Encoder::with_quality_and_params(
+        input.stream(),
+        Level::Best,
+        params,
+    );
+    let compressed = stream_to_vec(encoder);
+
+    // The window size is stored as `(lgwin - 17) << 1 | 1` in the first 4 bits
+    assert_eq!(compressed[0] & 0x0f, (24 - 17) << 1 | 1);
+    assert_eq!(utils::brotli::sync::decompress(&compressed), input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn brotli_futures_large_window() {
+    use async_compression::{BrotliParams, Level};
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![(0..10_000).map(|i| (i % 251) as u8).collect()]);
+
+    let params = BrotliParams::default().window_bits(30).large_window(true);
+    let encoder = utils::brotli::futures::bufread::Encoder::with_quality_and_params(
+        input.reader(),
+        Level::Fastest,
+        params,
+    );
+    let compressed = async_read_to_vec(encoder);
+
+    let mut decoder = utils::brotli::futures::bufread::Decoder::new(&compressed[..]);
+    assert!(block_on(decoder.read_to_end(&mut Vec::new())).is_err());
+
+    let mut decoder = utils::brotli::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.large_window(true);
+    assert_eq!(async_read_to_vec(decoder), input.bytes());
+}
@@ -0,0 +1,401 @@
+//! Tests running a custom codec through the generic adaptors.
+
+#[allow(unused)]
+use async_compression::{
+    codec::{Decode, Encode},
+    util::PartialBuffer,
+};
+#[allow(unused)]
+use std::io::Result;
+
+#[allow(unused)]
+mod utils;
+
+/// A trivial "compression" format that xors each byte with a fixed key, the end of the stream is
+/// only known once the input is exhausted.
+#[derive(Debug, Default)]
+struct Xor;
+
+#[allow(unused)]
+fn xor(
+    input: &mut PartialBuffer<impl AsRef<[u8]>>,
+    output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+) {
+    let len = std::cmp::min(input.unwritten().len(), output.unwritten().len());
+    for (out, byte) in output.unwritten_mut()[..len]
+        .iter_mut()
+        .zip(&input.unwritten()[..len])
+    {
+        *out = byte ^ 0xaa;
+    }
+    input.advance(len);
+    output.advance(len);
+}
+
+impl Encode for Xor {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        xor(input, output);
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn finish(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+impl Decode for Xor {
+    fn reinit(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        xor(input, output);
+        Ok(false)
+    }
+
+    fn flush(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn finish(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[allow(unused)]
+fn xored(input: &[u8]) -> Vec<u8> {
+    input.iter().map(|byte| byte ^ 0xaa).collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_custom_codec() {
+    use async_compression::stream::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::stream_to_vec(Encoder::new(input.stream(), Xor));
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let output = utils::prelude::stream_to_vec(Decoder::new(compressed.stream(), Xor));
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_bufread_custom_codec() {
+    use async_compression::futures::bufread::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::async_read_to_vec(Encoder::new(input.reader(), Xor));
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let output = utils::prelude::async_read_to_vec(Decoder::new(compressed.reader(), Xor));
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_custom_codec() {
+    use async_compression::futures::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::async_write_to_vec(
+        input.as_ref(),
+        |input| Box::pin(Encoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::async_write_to_vec(
+        &[compressed],
+        |input| Box::pin(Decoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_bufread_custom_codec() {
+    use async_compression::tokio_02::bufread::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed =
+        utils::prelude::tokio_02_read_to_vec(Encoder::new(input.tokio_02_reader(), Xor));
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let output =
+        utils::prelude::tokio_02_read_to_vec(Decoder::new(compressed.tokio_02_reader(), Xor));
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_write_custom_codec() {
+    use async_compression::tokio_02::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::tokio_02_write_to_vec(
+        input.as_ref(),
+        |input| Box::pin(Encoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::tokio_02_write_to_vec(
+        &[compressed],
+        |input| Box::pin(Decoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn tokio_bufread_custom_codec() {
+    use async_compression::tokio::bufread::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::tokio_read_to_vec(Encoder::new(input.tokio_reader(), Xor));
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let output = utils::prelude::tokio_read_to_vec(Decoder::new(compressed.tokio_reader(), Xor));
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn tokio_write_custom_codec() {
+    use async_compression::tokio::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::tokio_write_to_vec(
+        input.as_ref(),
+        |input| Box::pin(Encoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::tokio_write_to_vec(
+        &[compressed],
+        |input| Box::pin(Decoder::new(input, Xor)),
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_custom_codec_buffer_size() {
+    use async_compression::stream::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let mut encoder = Encoder::new(input.stream(), Xor);
+    encoder.buffer_size(1);
+    let compressed = utils::prelude::stream_to_vec(encoder);
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let mut decoder = Decoder::new(compressed.stream(), Xor);
+    decoder.buffer_size(1 << 20);
+    let output = utils::prelude::stream_to_vec(decoder);
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[cfg(feature = "stream")]
+#[should_panic(expected = "buffer size must be non-zero")]
+fn stream_custom_codec_zero_buffer_size() {
+    use async_compression::stream::Encoder;
+
+    let input = utils::InputStream::from(vec![vec![1, 2, 3]]);
+    Encoder::new(input.stream(), Xor).buffer_size(0);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_custom_codec_buffer_size() {
+    use async_compression::futures::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::async_write_to_vec(
+        input.as_ref(),
+        |input| {
+            let mut encoder = Encoder::new(input, Xor);
+            encoder.buffer_size(1);
+            Box::pin(encoder)
+        },
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::async_write_to_vec(
+        &[compressed],
+        |input| {
+            let mut decoder = Decoder::new(input, Xor);
+            decoder.buffer_size(1 << 20);
+            Box::pin(decoder)
+        },
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_custom_codec_resize_buffered() {
+    use async_compression::futures::write::Encoder;
+    use futures::{executor::block_on, io::AsyncWriteExt};
+
+    let mut encoder = Encoder::new(Vec::new(), Xor);
+    block_on(encoder.write_all(&[1, 2, 3, 4, 5, 6])).unwrap();
+    // Shrinking below what is still buffered must not lose any of it.
+    encoder.buffer_size(1);
+    block_on(encoder.write_all(&[7, 8])).unwrap();
+    block_on(encoder.close()).unwrap();
+    assert_eq!(encoder.into_inner(), xored(&[1, 2, 3, 4, 5, 6, 7, 8]));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn tokio_02_write_custom_codec_buffer_size() {
+    use async_compression::tokio_02::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::tokio_02_write_to_vec(
+        input.as_ref(),
+        |input| {
+            let mut encoder = Encoder::new(input, Xor);
+            encoder.buffer_size(1);
+            Box::pin(encoder)
+        },
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::tokio_02_write_to_vec(
+        &[compressed],
+        |input| {
+            let mut decoder = Decoder::new(input, Xor);
+            decoder.buffer_size(1 << 20);
+            Box::pin(decoder)
+        },
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn tokio_write_custom_codec_buffer_size() {
+    use async_compression::tokio::write::{Decoder, Encoder};
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = utils::prelude::tokio_write_to_vec(
+        input.as_ref(),
+        |input| {
+            let mut encoder = Encoder::new(input, Xor);
+            encoder.buffer_size(1);
+            Box::pin(encoder)
+        },
+        2,
+    );
+    assert_eq!(compressed, xored(&[1, 2, 3, 4, 5, 6]));
+
+    let output = utils::prelude::tokio_write_to_vec(
+        &[compressed],
+        |input| {
+            let mut decoder = Decoder::new(input, Xor);
+            decoder.buffer_size(1 << 20);
+            Box::pin(decoder)
+        },
+        2,
+    );
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn futures_write_custom_codec_write_after_close() {
+    use async_compression::futures::write::Encoder;
+    use futures::{executor::block_on, io::AsyncWriteExt};
+
+    let mut encoder = Encoder::new(Vec::new(), Xor);
+    block_on(encoder.write_all(&[1, 2, 3])).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    let err = block_on(encoder.write_all(&[4, 5, 6])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    let err = block_on(encoder.flush()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    assert_eq!(encoder.into_inner(), xored(&[1, 2, 3]));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn tokio_write_custom_codec_write_after_shutdown() {
+    use async_compression::tokio::write::Encoder;
+    use tokio::io::AsyncWriteExt;
+    use utils::prelude::block_on;
+
+    let mut encoder = Encoder::new(Vec::new(), Xor);
+    block_on(encoder.write_all(&[1, 2, 3])).unwrap();
+    block_on(encoder.shutdown()).unwrap();
+
+    let err = block_on(encoder.write_all(&[4, 5, 6])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    assert_eq!(encoder.into_inner(), xored(&[1, 2, 3]));
+}
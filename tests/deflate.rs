@@ -2,3 +2,239 @@
 mod utils;
 
 test_cases!(deflate);
+
+/// A raw content dictionary, any bytes can be used as one.
+#[allow(unused)]
+const DICTIONARY: &[u8] =
+    br#"{"id": 0, "name": "", "tags": [], "created": "2020-01-01T00:00:00Z"}"#;
+
+#[allow(unused)]
+const MESSAGE: &[u8] =
+    br#"{"id": 42, "name": "example", "tags": ["a"], "created": "2020-06-01T12:00:00Z"}"#;
+
+/// `MESSAGE` compressed by zlib with `DICTIONARY` preset and no zlib framing.
+#[allow(unused)]
+const COMPRESSED: &[u8] = &[
+    171, 134, 152, 97, 98, 132, 100, 72, 106, 69, 98, 110, 65, 78, 42, 178, 89, 74, 137, 74, 88,
+    141, 51, 3, 25, 103, 104, 4, 55, 14, 0,
+];
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn deflate_stream_dictionary_roundtrip() {
+    use utils::prelude::*;
+
+    let encoder = utils::deflate::stream::Encoder::with_quality_and_dictionary(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+        Level::Best,
+        DICTIONARY,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    let plain = stream_to_vec(utils::deflate::stream::Encoder::with_quality(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+        Level::Best,
+    ));
+    assert!(compressed.len() < plain.len());
+
+    let decoder = utils::deflate::stream::Decoder::with_dictionary(
+        utils::InputStream::from(vec![compressed]).stream(),
+        DICTIONARY,
+    )
+    .unwrap();
+    assert_eq!(stream_to_vec(decoder), MESSAGE);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_futures_decompress_with_dictionary() {
+    use utils::prelude::*;
+
+    let decoder = utils::deflate::futures::bufread::Decoder::with_dictionary(
+        utils::InputStream::from(vec![COMPRESSED.to_vec()]).reader(),
+        DICTIONARY,
+    )
+    .unwrap();
+    assert_eq!(async_read_to_vec(decoder), MESSAGE);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_futures_compress_with_dictionary() {
+    use utils::prelude::*;
+
+    // The dictionary only primes the window, so the output is exactly zlib's
+    let encoder = utils::deflate::futures::bufread::Encoder::with_quality_and_dictionary(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).reader(),
+        Level::Default,
+        DICTIONARY,
+    )
+    .unwrap();
+    assert_eq!(async_read_to_vec(encoder), COMPRESSED);
+}
+
+/// Rows of mostly repeated bytes, like image scanlines.
+#[allow(unused)]
+fn scanlines() -> Vec<u8> {
+    (0..64u8)
+        .flat_map(|row| {
+            vec![row; 200]
+                .into_iter()
+                .chain(vec![row.wrapping_mul(37); 56])
+        })
+        .collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn deflate_stream_compress_with_strategies() {
+    use async_compression::{FlateParams, FlateStrategy};
+    use utils::prelude::*;
+
+    let input = scanlines();
+
+    let compress = |strategy| {
+        stream_to_vec(utils::deflate::stream::Encoder::with_quality_and_params(
+            utils::InputStream::from(vec![input.clone()]).stream(),
+            Level::Default,
+            FlateParams::default().strategy(strategy),
+        ))
+    };
+
+    let huffman_only = compress(FlateStrategy::HuffmanOnly);
+    let rle = compress(FlateStrategy::Rle);
+    assert!(rle.len() * 10 < huffman_only.len());
+
+    // Only the first block's header is needed to tell fixed codes (type 1) from dynamic (type 2)
+    assert_eq!(compress(FlateStrategy::Fixed)[0] >> 1 & 3, 1);
+    assert_eq!(compress(FlateStrategy::Default)[0] >> 1 & 3, 2);
+
+    for strategy in [
+        FlateStrategy::Default,
+        FlateStrategy::Filtered,
+        FlateStrategy::HuffmanOnly,
+        FlateStrategy::Rle,
+        FlateStrategy::Fixed,
+    ]
+    .iter()
+    {
+        let output = utils::deflate::sync::decompress(&compress(*strategy));
+        assert_eq!(output, input);
+    }
+}
+
+/// Common words in a pseudo-random order.
+#[allow(unused)]
+fn words() -> Vec<u8> {
+    let words = [
+        "alpha ", "beta ", "gamma ", "delta ", "epsilon ", "zeta ", "eta ", "theta ",
+    ];
+    let mut state = 1u32;
+    (0..20_000)
+        .flat_map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            words[(state >> 16) as usize % words.len()].bytes()
+        })
+        .collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn deflate_stream_compress_with_mem_level() {
+    use async_compression::FlateParams;
+    use utils::prelude::*;
+
+    let input = words();
+
+    let compress = |level| {
+        stream_to_vec(utils::deflate::stream::Encoder::with_quality_and_params(
+            utils::InputStream::from(vec![input.clone()]).stream(),
+            Level::Default,
+            FlateParams::default().mem_level(level),
+        ))
+    };
+
+    // The smaller hash table and block buffer find fewer matches
+    let small = compress(1);
+    let default = compress(8);
+    assert!(default.len() < small.len());
+    assert_eq!(utils::deflate::sync::decompress(&small), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_futures_compress_with_small_window() {
+    use async_compression::FlateParams;
+    use utils::prelude::*;
+
+    // Repeats further apart than a 512 byte window can't be referenced
+    let block: Vec<u8> = (0..1024u32).map(|i| (i * i % 251) as u8).collect();
+    let input = [&block[..]; 8].concat();
+
+    let compress = |bits| {
+        async_read_to_vec(
+            utils::deflate::futures::bufread::Encoder::with_quality_and_params(
+                utils::InputStream::from(vec![input.clone()]).reader(),
+                Level::Best,
+                FlateParams::default().window_bits(bits),
+            ),
+        )
+    };
+
+    let small = compress(9);
+    let default = compress(15);
+    assert!(default.len() * 2 < small.len());
+    assert_eq!(utils::deflate::sync::decompress(&small), input);
+}
+
+/// A block of pseudo-random bytes repeated, so matches are 1 KiB back.
+#[allow(unused)]
+fn repeated_block() -> Vec<u8> {
+    let mut state = 1u32;
+    let block: Vec<u8> = (0..1024)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect();
+    [&block[..]; 8].concat()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_futures_decompress_with_window_bits() {
+    use async_compression::FlateParams;
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let input = repeated_block();
+
+    let compressed = utils::deflate::sync::compress(&input);
+    let mut decoder = utils::deflate::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.window_bits(9);
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let compressed = async_read_to_vec(
+        utils::deflate::futures::bufread::Encoder::with_quality_and_params(
+            &input[..],
+            Level::Default,
+            FlateParams::default().window_bits(9),
+        ),
+    );
+    // Split up so matches span reads
+    let mut decoder = utils::deflate::futures::bufread::Decoder::new(
+        utils::InputStream::from(compressed.chunks(7).map(Vec::from).collect::<Vec<_>>()).reader(),
+    );
+    decoder.window_bits(9);
+    assert_eq!(async_read_to_vec(decoder), input);
+}
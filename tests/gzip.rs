@@ -160,3 +160,993 @@ fn gzip_bufread_chunks_decompress_with_extra_header() {
 
     assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
 }
+
+#[allow(unused)]
+fn compress_member(filename: &str, mtime: u32, data: &[u8]) -> Vec<u8> {
+    use flate2::{Compression, GzBuilder};
+    use std::io::Write;
+
+    let mut bytes = Vec::new();
+    {
+        let mut gz = GzBuilder::new()
+            .filename(filename)
+            .comment("test file, please delete")
+            .mtime(mtime)
+            .extra(vec![b'A', b'B', 2, 0, 7, 8])
+            .operating_system(3)
+            .write(&mut bytes, Compression::fast());
+
+        gz.write_all(data).unwrap();
+    }
+
+    bytes
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_header() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let bytes = compress_member("hello_world.txt", 1_234_567, &[1, 2, 3, 4, 5, 6]);
+    let stream = utils::InputStream::from(bytes.chunks(2).map(Vec::from).collect::<Vec<_>>());
+
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(stream.reader());
+    assert_eq!(decoder.header(), None);
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let header = decoder.header().unwrap();
+    assert_eq!(header.filename(), Some(&b"hello_world.txt"[..]));
+    assert_eq!(header.comment(), Some(&b"test file, please delete"[..]));
+    assert_eq!(header.extra(), Some(&[b'A', b'B', 2, 0, 7, 8][..]));
+    assert_eq!(header.mtime(), 1_234_567);
+    assert_eq!(header.operating_system(), 3);
+    assert!(!header.is_text());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_multiple_members_headers() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let mut bytes = compress_member("first", 1, &[1, 2, 3]);
+    bytes.extend(compress_member("second", 2, &[4, 5, 6]));
+    let stream = utils::InputStream::from(vec![bytes]);
+
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(stream.reader());
+    decoder.multiple_members(true);
+
+    let mut members = Vec::new();
+    loop {
+        let mut buf = [0; 1024];
+        let len = block_on(decoder.read(&mut buf)).unwrap();
+        if len == 0 {
+            break;
+        }
+        let filename = decoder.header().unwrap().filename().unwrap().to_vec();
+        members.push((filename, buf[..len].to_vec()));
+    }
+
+    assert_eq!(
+        members,
+        vec![
+            (b"first".to_vec(), vec![1, 2, 3]),
+            (b"second".to_vec(), vec![4, 5, 6]),
+        ]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_unterminated_filename() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    // A filename which never ends must be rejected rather than buffered in full
+    let mut bytes = vec![0x1f, 0x8b, 0x08, 0b0000_1000, 0, 0, 0, 0, 0, 0xff];
+    bytes.extend(std::iter::repeat(b'a').take(1024 * 1024));
+    let stream = utils::InputStream::from(bytes.chunks(1024).map(Vec::from).collect::<Vec<_>>());
+
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(stream.reader());
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_bufread_decompress_multiple_members_headers() {
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let mut bytes = compress_member("first", 1, &[1, 2, 3]);
+    bytes.extend(compress_member("second", 2, &[4, 5, 6]));
+    let stream = utils::InputStream::from(vec![bytes]);
+
+    let mut decoder = utils::gzip::tokio::bufread::Decoder::new(stream.tokio_reader());
+    decoder.multiple_members(true);
+
+    let mut members = Vec::new();
+    loop {
+        let mut buf = [0; 1024];
+        let len = block_on(decoder.read(&mut buf)).unwrap();
+        if len == 0 {
+            break;
+        }
+        let filename = decoder.header().unwrap().filename().unwrap().to_vec();
+        members.push((filename, buf[..len].to_vec()));
+    }
+
+    assert_eq!(
+        members,
+        vec![
+            (b"first".to_vec(), vec![1, 2, 3]),
+            (b"second".to_vec(), vec![4, 5, 6]),
+        ]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_decompress_multiple_members_headers() {
+    use futures::stream::StreamExt;
+    use utils::prelude::*;
+
+    let mut bytes = compress_member("first", 1, &[1, 2, 3]);
+    bytes.extend(compress_member("second", 2, &[4, 5, 6]));
+    let stream = utils::InputStream::from(vec![bytes]);
+
+    let decoder = utils::gzip::stream::Decoder::new(stream.stream());
+    pin_mut!(decoder);
+    decoder.multiple_members(true);
+
+    let mut members = Vec::new();
+    while let Some(chunk) = block_on(decoder.next()) {
+        let header = decoder.header().unwrap();
+        members.push((
+            header.filename().unwrap().to_vec(),
+            header.mtime(),
+            chunk.unwrap().to_vec(),
+        ));
+    }
+
+    assert_eq!(
+        members,
+        vec![
+            (b"first".to_vec(), 1, vec![1, 2, 3]),
+            (b"second".to_vec(), 2, vec![4, 5, 6]),
+        ]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_decompress_header() {
+    use futures::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let bytes = compress_member("hello_world.txt", 42, &[1, 2, 3, 4, 5, 6]);
+
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    block_on(decoder.write_all(&bytes)).unwrap();
+    block_on(decoder.close()).unwrap();
+
+    let header = decoder.header().unwrap();
+    assert_eq!(header.filename(), Some(&b"hello_world.txt"[..]));
+    assert_eq!(header.mtime(), 42);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[allow(unused)]
+fn header() -> async_compression::GzipHeader {
+    async_compression::GzipHeaderBuilder::new()
+        .filename("hello_world.txt")
+        .comment("test file, please delete")
+        .mtime(1_234_567)
+        .extra_subfield(*b"AB", &[7, 8])
+        .operating_system(3)
+        .header_crc(true)
+        .build()
+}
+
+#[allow(unused)]
+fn assert_sync_header(compressed: &[u8]) {
+    use std::io::Read;
+
+    let mut decoder = flate2::read::GzDecoder::new(compressed);
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let header = decoder.header().unwrap();
+    assert_eq!(header.filename(), Some(&b"hello_world.txt"[..]));
+    assert_eq!(header.comment(), Some(&b"test file, please delete"[..]));
+    assert_eq!(header.extra(), Some(&[b'A', b'B', 2, 0, 7, 8][..]));
+    assert_eq!(header.mtime(), 1_234_567);
+    assert_eq!(header.operating_system(), 3);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_compress_with_default_header() {
+    use utils::prelude::*;
+
+    // The default header matches what the encoders write without one
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let header = async_compression::GzipHeaderBuilder::new().build();
+    let with_header = stream_to_vec(utils::gzip::stream::Encoder::with_quality_and_header(
+        input.stream(),
+        Level::Fastest,
+        header,
+    ));
+    let without_header = stream_to_vec(utils::gzip::stream::Encoder::with_quality(
+        input.stream(),
+        Level::Fastest,
+    ));
+
+    assert_eq!(with_header, without_header);
+    assert_eq!(
+        with_header[..10],
+        [0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0x04, 0xff]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_compress_with_header() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let encoder = utils::gzip::stream::Encoder::with_quality_and_header(
+        input.stream(),
+        Level::Fastest,
+        header(),
+    );
+    let compressed = stream_to_vec(encoder);
+
+    assert_sync_header(&compressed);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_compress_with_header() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let encoder = utils::gzip::futures::bufread::Encoder::with_quality_and_header(
+        input.reader(),
+        Level::Default,
+        header(),
+    );
+    let compressed = async_read_to_vec(encoder);
+
+    assert_sync_header(&compressed);
+
+    // And our own decoder reads back exactly the same header
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(
+        utils::InputStream::from(vec![compressed]).reader(),
+    );
+    let mut output = Vec::new();
+    block_on(futures::io::AsyncReadExt::read_to_end(
+        &mut decoder,
+        &mut output,
+    ))
+    .unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+    assert_eq!(decoder.header(), Some(&header()));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_compress_with_header() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let compressed = async_write_to_vec(
+        input.as_ref(),
+        |input| {
+            Box::pin(
+                utils::gzip::futures::write::Encoder::with_quality_and_header(
+                    input,
+                    Level::Best,
+                    header(),
+                ),
+            )
+        },
+        2,
+    );
+
+    assert_sync_header(&compressed);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_write_compress_with_header() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let compressed = tokio_write_to_vec(
+        input.as_ref(),
+        |input| {
+            Box::pin(utils::gzip::tokio::write::Encoder::with_quality_and_header(
+                input,
+                Level::Best,
+                header(),
+            ))
+        },
+        2,
+    );
+
+    assert_sync_header(&compressed);
+}
+
+/// Compresses the data with the `FHCRC` flag set, optionally with an invalid header CRC.
+#[allow(unused)]
+fn compress_with_header_crc(data: &[u8], valid: bool) -> Vec<u8> {
+    let (mut header, body, footer) = split(utils::gzip::sync::compress(data));
+
+    header[3] |= 0b0000_0010;
+    let mut crc = flate2::Crc::new();
+    crc.update(&header);
+    let mut header_crc = (crc.sum() as u16).to_le_bytes();
+    if !valid {
+        header_crc[0] ^= 0xff;
+    }
+
+    [header, header_crc.to_vec(), body, footer].concat()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_decompress_header_crc() {
+    use futures::stream::StreamExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let output = utils::gzip::stream::decompress(stream.stream());
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let decoder = utils::gzip::stream::Decoder::new(stream.stream());
+    pin_mut!(decoder);
+    let err = block_on(decoder.next()).unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_decompress_header_crc() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let output = utils::gzip::futures::bufread::decompress(stream.reader());
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(stream.reader());
+    let err = block_on(decoder.read_to_end(&mut Vec::new())).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_decompress_header_crc() {
+    use futures::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let output = utils::gzip::futures::write::decompress(&[compressed], 65_536);
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn gzip_tokio_02_bufread_decompress_header_crc() {
+    use tokio_02::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let output = utils::gzip::tokio_02::bufread::decompress(stream.tokio_02_reader());
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let mut decoder = utils::gzip::tokio_02::bufread::Decoder::new(stream.tokio_02_reader());
+    let err = block_on(decoder.read_to_end(&mut Vec::new())).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn gzip_tokio_02_write_decompress_header_crc() {
+    use tokio_02::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let output = utils::gzip::tokio_02::write::decompress(&[compressed], 65_536);
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let mut decoder = utils::gzip::tokio_02::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_bufread_decompress_header_crc() {
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let output = utils::gzip::tokio::bufread::decompress(stream.tokio_reader());
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let stream = utils::InputStream::from(vec![compressed]);
+    let mut decoder = utils::gzip::tokio::bufread::Decoder::new(stream.tokio_reader());
+    let err = block_on(decoder.read_to_end(&mut Vec::new())).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_write_decompress_header_crc() {
+    use tokio::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], true);
+    let output = utils::gzip::tokio::write::decompress(&[compressed], 65_536);
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let compressed = compress_with_header_crc(&[1, 2, 3, 4, 5, 6], false);
+    let mut decoder = utils::gzip::tokio::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_buffer_size() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![(0..20_000).map(|i| (i % 7) as u8).collect()]);
+
+    let mut encoder = utils::gzip::stream::Encoder::new(input.stream());
+    encoder.buffer_size(16);
+    let compressed = stream_to_vec(encoder);
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input.bytes());
+
+    let compressed = utils::InputStream::from(vec![compressed]);
+    let mut decoder = utils::gzip::stream::Decoder::new(compressed.stream());
+    decoder.buffer_size(16);
+    assert_eq!(stream_to_vec(decoder), input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_buffer_size() {
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![(0..20_000).map(|i| (i % 7) as u8).collect()]);
+
+    let compressed = async_write_to_vec(
+        input.as_ref(),
+        |input| {
+            let mut encoder = utils::gzip::futures::write::Encoder::new(input);
+            encoder.buffer_size(16);
+            Box::pin(encoder)
+        },
+        65_536,
+    );
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input.bytes());
+
+    let output = async_write_to_vec(
+        &[compressed],
+        |input| {
+            let mut decoder = utils::gzip::futures::write::Decoder::new(input);
+            decoder.buffer_size(1 << 20);
+            Box::pin(decoder)
+        },
+        65_536,
+    );
+    assert_eq!(output, input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_decompress_trailing_data() {
+    use futures::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = [
+        utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        vec![7, 8, 9],
+    ]
+    .concat();
+
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.write_all(&[10])).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9, 10][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+
+    // Trailing data in a separate write from the end of the stream
+    let member = utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]);
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    block_on(decoder.write_all(&member)).unwrap();
+    let err = block_on(decoder.write_all(&[7, 8, 9])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::futures::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&member)).unwrap();
+    block_on(decoder.write_all(&[7, 8, 9])).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn gzip_tokio_02_write_decompress_trailing_data() {
+    use tokio_02::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = [
+        utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        vec![7, 8, 9],
+    ]
+    .concat();
+
+    let mut decoder = utils::gzip::tokio_02::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::tokio_02::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.shutdown()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+
+    // Trailing data in a separate write from the end of the stream
+    let member = utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]);
+    let mut decoder = utils::gzip::tokio_02::write::Decoder::new(Vec::new());
+    block_on(decoder.write_all(&member)).unwrap();
+    let err = block_on(decoder.write_all(&[7, 8, 9])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::tokio_02::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&member)).unwrap();
+    block_on(decoder.write_all(&[7, 8, 9])).unwrap();
+    block_on(decoder.shutdown()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_write_decompress_trailing_data() {
+    use tokio::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let compressed = [
+        utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        vec![7, 8, 9],
+    ]
+    .concat();
+
+    let mut decoder = utils::gzip::tokio::write::Decoder::new(Vec::new());
+    let err = block_on(decoder.write_all(&compressed)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::tokio::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.shutdown()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+
+    // Trailing data in a separate write from the end of the stream
+    let member = utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]);
+    let mut decoder = utils::gzip::tokio::write::Decoder::new(Vec::new());
+    block_on(decoder.write_all(&member)).unwrap();
+    let err = block_on(decoder.write_all(&[7, 8, 9])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut decoder = utils::gzip::tokio::write::Decoder::new(Vec::new());
+    decoder.collect_trailing_data(true);
+    block_on(decoder.write_all(&member)).unwrap();
+    block_on(decoder.write_all(&[7, 8, 9])).unwrap();
+    block_on(decoder.shutdown()).unwrap();
+    assert_eq!(decoder.trailing_data(), &[7, 8, 9][..]);
+    assert_eq!(decoder.into_inner(), &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[allow(unused)]
+fn members_with_trailing_data() -> Vec<u8> {
+    [
+        utils::gzip::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        utils::gzip::sync::compress(&[6, 5, 4, 3, 2, 1]),
+        b"trailing".to_vec(),
+    ]
+    .concat()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_stop_at_trailing_data() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = members_with_trailing_data();
+
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    assert!(block_on(decoder.read_to_end(&mut Vec::new())).is_err());
+
+    let mut decoder = utils::gzip::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-02")]
+fn gzip_tokio_02_bufread_stop_at_trailing_data() {
+    use tokio_02::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = members_with_trailing_data();
+
+    let mut decoder = utils::gzip::tokio_02::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_bufread_stop_at_trailing_data() {
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = members_with_trailing_data();
+
+    let mut decoder = utils::gzip::tokio::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+}
+
+/// Compressible but not too repetitive input, large enough to be split into many blocks.
+#[allow(unused)]
+fn parallel_input() -> Vec<u8> {
+    (0..1_000_000u32)
+        .flat_map(|i| format!("{} ", i % 1000 * 7919 % 10007).into_bytes())
+        .take(1 << 18)
+        .collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_parallel() {
+    use async_compression::futures::bufread::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    let input = parallel_input();
+
+    let compressed = async_read_to_vec(ParallelGzipEncoder::with_quality_and_block_size(
+        &input[..],
+        Level::Fastest,
+        4,
+        32 * 1024,
+    ));
+    let serial = async_read_to_vec(utils::gzip::futures::bufread::Encoder::with_quality(
+        &input[..],
+        Level::Fastest,
+    ));
+
+    // A single member, with blocks referencing the data before them
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input);
+    assert!(compressed.len() < serial.len() * 21 / 20);
+
+    let output = async_read_to_vec(utils::gzip::futures::bufread::Decoder::new(&compressed[..]));
+    assert_eq!(output, input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_parallel_wakes_instead_of_blocking() {
+    use async_compression::futures::bufread::ParallelGzipEncoder;
+    use futures::{
+        io::AsyncRead,
+        task::{waker, ArcWake},
+    };
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+    use utils::prelude::*;
+
+    struct Wakes(AtomicUsize);
+
+    impl ArcWake for Wakes {
+        fn wake_by_ref(arc: &Arc<Self>) {
+            arc.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let input = &parallel_input()[..64 * 1024];
+    let wakes = Arc::new(Wakes(AtomicUsize::new(0)));
+    let waker = waker(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    // A single worker can't keep up with small blocks, so reading has to wait on it
+    let mut encoder =
+        ParallelGzipEncoder::with_quality_and_block_size(input, Level::Fastest, 1, 4096);
+    let mut compressed = Vec::new();
+    let mut buffer = [0; 1024];
+    let mut pending = 0;
+    loop {
+        match Pin::new(&mut encoder).poll_read(&mut cx, &mut buffer) {
+            Poll::Ready(Ok(0)) => break,
+            Poll::Ready(Ok(len)) => compressed.extend_from_slice(&buffer[..len]),
+            Poll::Ready(Err(err)) => panic!("{}", err),
+            Poll::Pending => {
+                pending += 1;
+                while wakes.0.load(Ordering::SeqCst) < pending {
+                    std::thread::yield_now();
+                }
+            }
+        }
+    }
+
+    assert!(pending > 0);
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_write_parallel_small_blocks() {
+    use async_compression::futures::write::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![
+        vec![],
+        (0..100).collect(),
+        vec![7; 3],
+        (0..50).rev().collect(),
+    ]);
+
+    let compressed = async_write_to_vec(
+        input.as_ref(),
+        |output| {
+            Box::pin(ParallelGzipEncoder::with_quality_and_block_size(
+                output,
+                Level::Default,
+                3,
+                7,
+            ))
+        },
+        16,
+    );
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_parallel_empty() {
+    use async_compression::futures::bufread::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    let compressed = async_read_to_vec(ParallelGzipEncoder::new(&[][..], 2));
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), &[][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_futures_bufread_parallel_many_threads() {
+    use async_compression::futures::bufread::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    // Asking for more threads than could ever be started is limited to the number of cores
+    let input = parallel_input();
+    let compressed = async_read_to_vec(ParallelGzipEncoder::with_quality_and_block_size(
+        &input[..],
+        Level::Fastest,
+        usize::MAX,
+        32 * 1024,
+    ));
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_bufread_parallel() {
+    use async_compression::tokio::bufread::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    let input = parallel_input();
+
+    let compressed = tokio_read_to_vec(ParallelGzipEncoder::new(&input[..], 4));
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_write_parallel_flush() {
+    use async_compression::tokio::write::ParallelGzipEncoder;
+    use utils::prelude::*;
+
+    let input = parallel_input();
+
+    // Flushes after each chunk, each flush waits for all blocks so far to be written
+    let compressed = tokio_write_to_vec(
+        &input.chunks(100_000).map(Vec::from).collect::<Vec<_>>(),
+        |output| Box::pin(ParallelGzipEncoder::new(output, 4)),
+        usize::MAX,
+    );
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input);
+
+    let output = tokio_read_to_vec(utils::gzip::tokio::bufread::Decoder::new(&compressed[..]));
+    assert_eq!(output, input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_compress_with_params() {
+    use async_compression::{FlateParams, FlateStrategy};
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![vec![1, 1, 1, 1, 2, 2, 2, 2], vec![3; 100]]);
+
+    let compressed = stream_to_vec(utils::gzip::stream::Encoder::with_quality_and_params(
+        input.stream(),
+        Level::Best,
+        FlateParams::default()
+            .window_bits(9)
+            .mem_level(1)
+            .strategy(FlateStrategy::Rle),
+    ));
+
+    assert_eq!(utils::gzip::sync::decompress(&compressed), input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn gzip_stream_compress_with_header_and_params() {
+    use async_compression::FlateParams;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+    let compressed = stream_to_vec(
+        utils::gzip::stream::Encoder::with_quality_header_and_params(
+            input.stream(),
+            Level::Fastest,
+            header(),
+            FlateParams::default().window_bits(9),
+        ),
+    );
+
+    assert_sync_header(&compressed);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn gzip_tokio_bufread_decompress_with_window_bits() {
+    use async_compression::FlateParams;
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    // A pseudo-random block repeated, so matches are 1 KiB back
+    let mut state = 1u32;
+    let block: Vec<u8> = (0..1024)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect();
+    let input = [&block[..]; 8].concat();
+
+    let compressed = utils::gzip::sync::compress(&input);
+    let mut decoder = utils::gzip::tokio::bufread::Decoder::new(&compressed[..]);
+    decoder.window_bits(9);
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // Each member is checked from its start
+    let small = stream_to_vec(utils::gzip::stream::Encoder::with_quality_and_params(
+        utils::InputStream::from(vec![input.clone()]).stream(),
+        Level::Default,
+        FlateParams::default().window_bits(10),
+    ));
+    let compressed = [&small[..], &small[..]].concat();
+    let mut decoder = utils::gzip::tokio::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.window_bits(10);
+    assert_eq!(
+        tokio_read_to_vec(decoder),
+        [&input[..], &input[..]].concat()
+    );
+}
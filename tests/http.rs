@@ -0,0 +1,236 @@
+//! Tests for the HTTP header helpers and decoding bodies with multiple encodings.
+
+#[allow(unused)]
+use async_compression::{http, Algorithm};
+#[allow(unused)]
+use std::iter::FromIterator;
+
+#[allow(unused)]
+mod utils;
+
+#[test]
+#[cfg(all(feature = "gzip", feature = "brotli", feature = "zlib"))]
+fn parse_content_encoding() {
+    assert_eq!(http::parse_content_encoding("").unwrap(), []);
+    assert_eq!(http::parse_content_encoding("identity").unwrap(), []);
+    assert_eq!(
+        http::parse_content_encoding("gzip, br").unwrap(),
+        [Algorithm::Gzip, Algorithm::Brotli]
+    );
+    assert_eq!(
+        http::parse_content_encoding(" X-Gzip ,, identity,deflate ").unwrap(),
+        [Algorithm::Gzip, Algorithm::Zlib]
+    );
+}
+
+#[test]
+fn parse_content_encoding_unsupported() {
+    let err = http::parse_content_encoding("identity, compress").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<async_compression::UnsupportedEncoding>()
+        .unwrap();
+    assert_eq!(err.encoding(), "compress");
+}
+
+#[test]
+#[cfg(all(
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zlib",
+    feature = "zstd"
+))]
+fn select_encoding() {
+    assert_eq!(http::select_encoding(""), None);
+    assert_eq!(http::select_encoding("identity"), None);
+    assert_eq!(
+        http::select_encoding("compress, gzip"),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(
+        http::select_encoding("deflate, gzip"),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(
+        http::select_encoding("gzip;q=0.8, deflate;q=1"),
+        Some(Algorithm::Zlib)
+    );
+    assert_eq!(
+        http::select_encoding("br;q=0.5, GZIP ; Q=0.501"),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(http::select_encoding("*"), Some(Algorithm::Zstd));
+    assert_eq!(
+        http::select_encoding("zstd;q=0, br;q=0, *;q=0.1"),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(http::select_encoding("gzip;q=0, *;q=0"), None);
+    // Invalid weights ignore the element
+    assert_eq!(
+        http::select_encoding("br;q=2, gzip;q=0.1234, deflate"),
+        Some(Algorithm::Zlib)
+    );
+}
+
+#[test]
+#[cfg(all(feature = "gzip", feature = "brotli"))]
+fn content_coding() {
+    assert_eq!(http::content_coding(Algorithm::Gzip), Some("gzip"));
+    assert_eq!(http::content_coding(Algorithm::Brotli), Some("br"));
+}
+
+/// Returns some data, and the same data with gzip then brotli applied.
+#[allow(unused)]
+#[cfg(all(feature = "gzip", feature = "brotli"))]
+fn gzip_br() -> (Vec<u8>, Vec<u8>) {
+    let input = Vec::from_iter((0..60_000).map(|i| (i % 251) as u8));
+    let encoded = utils::brotli::sync::compress(&utils::gzip::sync::compress(&input));
+    (input, encoded)
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "stream", feature = "gzip", feature = "brotli"))]
+fn stream_chain() {
+    use async_compression::stream::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+    let encoded = utils::InputStream::from(encoded.chunks(1000).map(Vec::from).collect::<Vec<_>>());
+
+    let decoder = ChainDecoder::from_content_encoding(encoded.stream(), "gzip, br").unwrap();
+    assert_eq!(stream_to_vec(decoder), input);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(
+    feature = "stream",
+    feature = "gzip",
+    feature = "brotli",
+    feature = "zlib"
+))]
+fn stream_chain_three() {
+    use async_compression::stream::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+    let encoded = utils::zlib::sync::compress(&encoded);
+    let encoded = utils::InputStream::from(vec![encoded]);
+
+    let decoder =
+        ChainDecoder::from_content_encoding(encoded.stream(), "gzip, br, deflate").unwrap();
+    assert_eq!(stream_to_vec(decoder), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn stream_chain_identity() {
+    use async_compression::stream::ChainDecoder;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+    let decoder = ChainDecoder::from_content_encoding(input.stream(), "identity").unwrap();
+    assert_eq!(stream_to_vec(decoder), [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "futures-io", feature = "gzip", feature = "brotli"))]
+fn futures_bufread_chain() {
+    use async_compression::futures::bufread::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+    let encoded = utils::InputStream::from(vec![encoded]);
+
+    let decoder = ChainDecoder::new(encoded.reader(), &[Algorithm::Gzip, Algorithm::Brotli]);
+    assert_eq!(async_read_to_vec(decoder), input);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "futures-io", feature = "gzip", feature = "brotli"))]
+fn futures_bufread_chain_truncated() {
+    use async_compression::futures::bufread::ChainDecoder;
+    use utils::prelude::*;
+
+    let (_, mut encoded) = gzip_br();
+    encoded.truncate(encoded.len() - 10);
+    let encoded = utils::InputStream::from(vec![encoded]);
+
+    let decoder = ChainDecoder::new(encoded.reader(), &[Algorithm::Gzip, Algorithm::Brotli]);
+    pin_mut!(decoder);
+    let mut output = Vec::new();
+    assert!(block_on(futures::io::AsyncReadExt::read_to_end(
+        &mut decoder,
+        &mut output
+    ))
+    .is_err());
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "futures-io", feature = "gzip", feature = "brotli"))]
+fn futures_write_chain() {
+    use async_compression::futures::write::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+
+    let output = async_write_to_vec(
+        &[encoded],
+        |output| Box::pin(ChainDecoder::from_content_encoding(output, "gzip, br").unwrap()),
+        65_536,
+    );
+    assert_eq!(output, input);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "tokio-02", feature = "gzip", feature = "brotli"))]
+fn tokio_02_bufread_chain() {
+    use async_compression::tokio_02::bufread::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+    let encoded = utils::InputStream::from(vec![encoded]);
+
+    let decoder =
+        ChainDecoder::from_content_encoding(encoded.tokio_02_reader(), "gzip, br").unwrap();
+    assert_eq!(tokio_02_read_to_vec(decoder), input);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "tokio", feature = "gzip", feature = "brotli"))]
+fn tokio_bufread_chain() {
+    use async_compression::tokio::bufread::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+    let encoded = utils::InputStream::from(vec![encoded]);
+
+    let decoder = ChainDecoder::from_content_encoding(encoded.tokio_reader(), "gzip, br").unwrap();
+    assert_eq!(tokio_read_to_vec(decoder), input);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "tokio", feature = "gzip", feature = "brotli"))]
+fn tokio_write_chain() {
+    use async_compression::tokio::write::ChainDecoder;
+    use utils::prelude::*;
+
+    let (input, encoded) = gzip_br();
+
+    let output = tokio_write_to_vec(
+        &[encoded],
+        |output| Box::pin(ChainDecoder::from_content_encoding(output, "gzip, br").unwrap()),
+        65_536,
+    );
+    assert_eq!(output, input);
+}
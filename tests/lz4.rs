@@ -0,0 +1,93 @@
+#[allow(unused)]
+use std::iter::FromIterator;
+
+#[macro_use]
+mod utils;
+
+test_cases!(lz4);
+
+#[allow(unused)]
+use async_compression::{Lz4BlockSize, Lz4Params};
+
+#[allow(unused)]
+fn params() -> Lz4Params {
+    Lz4Params::default()
+        .block_size(Lz4BlockSize::Max256KB)
+        .block_independence(true)
+        .content_checksum(true)
+        .block_checksum(true)
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn lz4_stream_compress_with_params() {
+    use utils::prelude::*;
+
+    let input = Vec::from_iter((0..300_000).map(|i| (i % 251) as u8));
+    let stream = utils::InputStream::from(vec![input.clone()]);
+
+    let encoder = utils::lz4::stream::Encoder::with_quality_and_params(
+        stream.stream(),
+        Level::Fastest,
+        params(),
+    );
+    let compressed = stream_to_vec(encoder);
+
+    // FLG byte: version 01, independent blocks, block checksums and content checksum
+    assert_eq!(compressed[4], 0b0111_0100);
+    // BD byte: 256 KiB maximum block size
+    assert_eq!(compressed[5], 0b0101_0000);
+
+    let output = utils::lz4::sync::decompress(&compressed);
+    assert_eq!(output, input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn lz4_futures_bufread_compress_with_params() {
+    use utils::prelude::*;
+
+    let input = Vec::from_iter((0..300_000).map(|i| (i % 251) as u8));
+    let stream = utils::InputStream::from(vec![input.clone()]);
+
+    let encoder = utils::lz4::futures::bufread::Encoder::with_quality_and_params(
+        stream.reader(),
+        Level::Best,
+        params(),
+    );
+    let compressed = async_read_to_vec(encoder);
+
+    let output = utils::lz4::futures::bufread::decompress(
+        utils::InputStream::from(vec![compressed]).reader(),
+    );
+    assert_eq!(output, input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn lz4_futures_bufread_decompress_corrupt_checksum() {
+    use utils::prelude::*;
+
+    let encoder = utils::lz4::futures::bufread::Encoder::with_quality_and_params(
+        utils::InputStream::from([[1, 2, 3], [4, 5, 6]]).reader(),
+        Level::Default,
+        params(),
+    );
+    let mut compressed = async_read_to_vec(encoder);
+    let last = compressed.len() - 1;
+    compressed[last] ^= 0xff;
+
+    let decoder = utils::lz4::futures::bufread::Decoder::new(
+        utils::InputStream::from(vec![compressed]).reader(),
+    );
+    pin_mut!(decoder);
+    let mut output = Vec::new();
+    assert!(block_on(futures::io::AsyncReadExt::read_to_end(
+        &mut decoder,
+        &mut output
+    ))
+    .is_err());
+}
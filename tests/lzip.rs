@@ -0,0 +1,121 @@
+#[macro_use]
+mod utils;
+
+test_cases!(lzip);
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn lzip_futures_write_flush_ends_member() {
+    use futures::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let mut encoder = utils::lzip::futures::write::Encoder::new(Vec::new());
+
+    block_on(async {
+        encoder.write_all(&[1, 2, 3, 4, 5, 6]).await.unwrap();
+        encoder.flush().await.unwrap();
+    });
+
+    // The first member is complete once flushed
+    let first = encoder.get_ref().clone();
+    assert_eq!(
+        utils::lzip::sync::decompress(&first),
+        &[1, 2, 3, 4, 5, 6][..]
+    );
+
+    block_on(async {
+        encoder.flush().await.unwrap();
+        encoder.write_all(&[6, 5, 4, 3, 2, 1]).await.unwrap();
+        encoder.close().await.unwrap();
+    });
+
+    // An empty flush doesn't write an empty member
+    let compressed = encoder.into_inner();
+    assert_eq!(&compressed[first.len()..first.len() + 4], b"LZIP");
+    assert_eq!(
+        utils::lzip::sync::decompress(&compressed),
+        &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]
+    );
+
+    let mut decoder = utils::lzip::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    assert_eq!(
+        async_read_to_vec(decoder),
+        &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn lzip_futures_bufread_stop_at_trailing_data() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = [
+        utils::lzip::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        utils::lzip::sync::compress(&[6, 5, 4, 3, 2, 1]),
+        b"trailing".to_vec(),
+    ]
+    .concat();
+
+    let mut decoder = utils::lzip::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn lzip_stream_invalid_trailer() {
+    use futures::stream::StreamExt;
+    use std::io::ErrorKind;
+    use utils::prelude::*;
+
+    let compressed = utils::lzip::sync::compress(&[1, 2, 3, 4, 5, 6]);
+    let trailer = compressed.len() - 20;
+
+    // The CRC, data size and member size are each checked
+    for &offset in &[0, 4, 12] {
+        let mut compressed = compressed.clone();
+        compressed[trailer + offset] ^= 1;
+
+        let stream = utils::InputStream::from(vec![compressed]);
+        let mut decoder = utils::lzip::stream::Decoder::new(stream.stream());
+        let err = block_on(async {
+            while let Some(result) = decoder.next().await {
+                result?;
+            }
+            Ok::<_, std::io::Error>(())
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn lzip_tokio_bufread_invalid_header() {
+    use std::io::ErrorKind;
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = utils::lzip::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+    // An unknown version, then a dictionary size below the 4 KiB minimum
+    for &(offset, byte) in &[(4, 2), (5, 11)] {
+        let mut compressed = compressed.clone();
+        compressed[offset] = byte;
+
+        let mut decoder = utils::lzip::tokio::bufread::Decoder::new(&compressed[..]);
+        let mut output = Vec::new();
+        let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -2,3 +2,28 @@
 mod utils;
 
 test_cases!(lzma);
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn lzma_tokio_write_max_memory_usage() {
+    use utils::prelude::*;
+
+    let compressed = utils::lzma::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+    let mut output = std::io::Cursor::new(Vec::new());
+    let mut decoder = utils::lzma::tokio::write::Decoder::new(&mut output);
+    decoder.max_memory_usage(Some(64 * 1024));
+    let err = block_on(tokio::io::AsyncWriteExt::write_all(
+        &mut decoder,
+        &compressed,
+    ))
+    .unwrap_err();
+    match limit_exceeded(&err) {
+        Some(LimitExceeded::MemoryUsage { limit, required }) => {
+            assert_eq!(limit, 64 * 1024);
+            assert!(required > limit);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
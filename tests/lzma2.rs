@@ -0,0 +1,57 @@
+#[macro_use]
+mod utils;
+
+test_cases!(lzma2);
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn lzma2_futures_with_dict_size() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    // Repeating a 64 KiB block needs a dictionary at least that large
+    let block: Vec<u8> = (0..65_536u32).map(|i| ((i * 7) ^ (i >> 9)) as u8).collect();
+    let input = [&block[..], &block[..]].concat();
+    let compressed = utils::lzma2::sync::compress(&input);
+
+    let decoder =
+        utils::lzma2::futures::bufread::Decoder::with_dict_size(&compressed[..], 128 * 1024)
+            .unwrap();
+    assert_eq!(async_read_to_vec(decoder), input);
+
+    let mut decoder =
+        utils::lzma2::futures::bufread::Decoder::with_dict_size(&compressed[..], 4096).unwrap();
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn lzma2_tokio_write_flush() {
+    use tokio::io::AsyncWriteExt;
+    use utils::prelude::*;
+
+    let mut encoder = utils::lzma2::tokio::write::Encoder::new(Vec::new());
+
+    block_on(async {
+        encoder.write_all(&[1, 2, 3, 4, 5, 6]).await.unwrap();
+        encoder.flush().await.unwrap();
+    });
+
+    // Everything written so far can be decoded, though the end marker is still missing
+    let mut decoder = utils::lzma2::tokio::write::Decoder::new(Vec::new());
+    block_on(async {
+        decoder.write_all(encoder.get_ref()).await.unwrap();
+        decoder.flush().await.unwrap();
+    });
+    assert_eq!(decoder.get_ref(), &[1, 2, 3, 4, 5, 6]);
+
+    block_on(encoder.shutdown()).unwrap();
+    assert_eq!(
+        utils::lzma2::sync::decompress(encoder.get_ref()),
+        &[1, 2, 3, 4, 5, 6][..]
+    );
+}
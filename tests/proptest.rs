@@ -111,6 +111,7 @@ macro_rules! tests {
                     mod write {
                         use crate::utils;
                         use proptest::{prelude::{any, ProptestConfig}, proptest};
+                        use std::iter::FromIterator;
 
                         proptest! {
                             #[test]
@@ -122,6 +123,38 @@ macro_rules! tests {
                                 let output = utils::$name::sync::decompress(&compressed);
                                 assert_eq!(output, input.bytes());
                             }
+
+                            #[test]
+                            fn decompress(
+                                ref input in any::<Vec<u8>>(),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = utils::$name::sync::compress(input);
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::$name::futures::write::decompress(stream.as_ref(), limit);
+                                assert_eq!(&output, input);
+                            }
+
+                            #[test]
+                            fn decompress_multiple_members(
+                                ref input in proptest::collection::vec(any::<Vec<u8>>(), 1..4),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = Vec::from_iter(input.iter().flat_map(|member| utils::$name::sync::compress(member)));
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::prelude::async_write_to_vec(
+                                    stream.as_ref(),
+                                    |input| {
+                                        let mut decoder = utils::$name::futures::write::Decoder::new(input);
+                                        decoder.multiple_members(true);
+                                        Box::pin(decoder)
+                                    },
+                                    limit,
+                                );
+                                assert_eq!(output, input.concat());
+                            }
                         }
 
                         proptest! {
@@ -155,7 +188,7 @@ macro_rules! tests {
                         proptest! {
                             #[test]
                             fn compress(ref input in any::<utils::InputStream>()) {
-                                let compressed = utils::$name::tokio_02::bufread::compress(input.tokio_reader());
+                                let compressed = utils::$name::tokio_02::bufread::compress(input.tokio_02_reader());
                                 let output = utils::$name::sync::decompress(&compressed);
                                 assert_eq!(output, input.bytes());
                             }
@@ -167,7 +200,7 @@ macro_rules! tests {
                             ) {
                                 let compressed = utils::$name::sync::compress(input);
                                 let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
-                                let output = utils::$name::tokio_02::bufread::decompress(stream.tokio_reader());
+                                let output = utils::$name::tokio_02::bufread::decompress(stream.tokio_02_reader());
                                 assert_eq!(&output, input);
                             }
                         }
@@ -180,8 +213,8 @@ macro_rules! tests {
                                 ref input in any::<utils::InputStream>(),
                                 level in crate::any_level(),
                             ) {
-                                let encoder = utils::$name::tokio_02::bufread::Encoder::with_quality(input.tokio_reader(), level);
-                                let compressed = utils::prelude::tokio_read_to_vec(encoder);
+                                let encoder = utils::$name::tokio_02::bufread::Encoder::with_quality(input.tokio_02_reader(), level);
+                                let compressed = utils::prelude::tokio_02_read_to_vec(encoder);
                                 let output = utils::$name::sync::decompress(&compressed);
                                 assert_eq!(output, input.bytes());
                             }
@@ -191,6 +224,7 @@ macro_rules! tests {
                     mod write {
                         use crate::utils;
                         use proptest::{prelude::{any, ProptestConfig}, proptest};
+                        use std::iter::FromIterator;
 
                         proptest! {
                             #[test]
@@ -202,6 +236,38 @@ macro_rules! tests {
                                 let output = utils::$name::sync::decompress(&compressed);
                                 assert_eq!(output, input.bytes());
                             }
+
+                            #[test]
+                            fn decompress(
+                                ref input in any::<Vec<u8>>(),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = utils::$name::sync::compress(input);
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::$name::tokio_02::write::decompress(stream.as_ref(), limit);
+                                assert_eq!(&output, input);
+                            }
+
+                            #[test]
+                            fn decompress_multiple_members(
+                                ref input in proptest::collection::vec(any::<Vec<u8>>(), 1..4),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = Vec::from_iter(input.iter().flat_map(|member| utils::$name::sync::compress(member)));
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::prelude::tokio_02_write_to_vec(
+                                    stream.as_ref(),
+                                    |input| {
+                                        let mut decoder = utils::$name::tokio_02::write::Decoder::new(input);
+                                        decoder.multiple_members(true);
+                                        Box::pin(decoder)
+                                    },
+                                    limit,
+                                );
+                                assert_eq!(output, input.concat());
+                            }
                         }
 
                         proptest! {
@@ -213,7 +279,7 @@ macro_rules! tests {
                                 limit in 1..20usize,
                                 level in crate::any_level(),
                             ) {
-                                let compressed = utils::prelude::tokio_write_to_vec(
+                                let compressed = utils::prelude::tokio_02_write_to_vec(
                                     input.as_ref(),
                                     |input| Box::pin(utils::$name::tokio_02::write::Encoder::with_quality(input, level)),
                                     limit,
@@ -224,6 +290,119 @@ macro_rules! tests {
                         }
                     }
                 }
+
+                #[cfg(feature = "tokio")]
+                mod tokio {
+                    mod bufread {
+                        use crate::utils;
+                        use proptest::{prelude::{any, ProptestConfig}, proptest};
+                        use std::iter::FromIterator;
+
+                        proptest! {
+                            #[test]
+                            fn compress(ref input in any::<utils::InputStream>()) {
+                                let compressed = utils::$name::tokio::bufread::compress(input.tokio_reader());
+                                let output = utils::$name::sync::decompress(&compressed);
+                                assert_eq!(output, input.bytes());
+                            }
+
+                            #[test]
+                            fn decompress(
+                                ref input in any::<Vec<u8>>(),
+                                chunk_size in 1..20usize,
+                            ) {
+                                let compressed = utils::$name::sync::compress(input);
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::$name::tokio::bufread::decompress(stream.tokio_reader());
+                                assert_eq!(&output, input);
+                            }
+                        }
+
+                        proptest! {
+                            #![proptest_config(ProptestConfig::with_cases(32))]
+
+                            #[test]
+                            fn compress_with_level(
+                                ref input in any::<utils::InputStream>(),
+                                level in crate::any_level(),
+                            ) {
+                                let encoder = utils::$name::tokio::bufread::Encoder::with_quality(input.tokio_reader(), level);
+                                let compressed = utils::prelude::tokio_read_to_vec(encoder);
+                                let output = utils::$name::sync::decompress(&compressed);
+                                assert_eq!(output, input.bytes());
+                            }
+                        }
+                    }
+
+                    mod write {
+                        use crate::utils;
+                        use proptest::{prelude::{any, ProptestConfig}, proptest};
+                        use std::iter::FromIterator;
+
+                        proptest! {
+                            #[test]
+                            fn compress(
+                                ref input in any::<utils::InputStream>(),
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = utils::$name::tokio::write::compress(input.as_ref(), limit);
+                                let output = utils::$name::sync::decompress(&compressed);
+                                assert_eq!(output, input.bytes());
+                            }
+
+                            #[test]
+                            fn decompress(
+                                ref input in any::<Vec<u8>>(),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = utils::$name::sync::compress(input);
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::$name::tokio::write::decompress(stream.as_ref(), limit);
+                                assert_eq!(&output, input);
+                            }
+
+                            #[test]
+                            fn decompress_multiple_members(
+                                ref input in proptest::collection::vec(any::<Vec<u8>>(), 1..4),
+                                chunk_size in 1..20usize,
+                                limit in 1..20usize,
+                            ) {
+                                let compressed = Vec::from_iter(input.iter().flat_map(|member| utils::$name::sync::compress(member)));
+                                let stream = utils::InputStream::from(Vec::from_iter(compressed.chunks(chunk_size).map(Vec::from)));
+                                let output = utils::prelude::tokio_write_to_vec(
+                                    stream.as_ref(),
+                                    |input| {
+                                        let mut decoder = utils::$name::tokio::write::Decoder::new(input);
+                                        decoder.multiple_members(true);
+                                        Box::pin(decoder)
+                                    },
+                                    limit,
+                                );
+                                assert_eq!(output, input.concat());
+                            }
+                        }
+
+                        proptest! {
+                            #![proptest_config(ProptestConfig::with_cases(32))]
+
+                            #[test]
+                            fn compress_with_level(
+                                ref input in any::<utils::InputStream>(),
+                                limit in 1..20usize,
+                                level in crate::any_level(),
+                            ) {
+                                let compressed = utils::prelude::tokio_write_to_vec(
+                                    input.as_ref(),
+                                    |input| Box::pin(utils::$name::tokio::write::Encoder::with_quality(input, level)),
+                                    limit,
+                                );
+                                let output = utils::$name::sync::decompress(&compressed);
+                                assert_eq!(output, input.bytes());
+                            }
+                        }
+                    }
+                }
             }
         )*
     }
@@ -235,7 +414,10 @@ mod proptest {
         bzip2("bzip2"),
         deflate("deflate"),
         gzip("gzip"),
+        lz4("lz4"),
+        lzip("lzip"),
         lzma("lzma"),
+        lzma2("lzma2"),
         xz("xz"),
         zlib("zlib"),
         zstd("zstd"),
@@ -2,7 +2,9 @@
 
 #[cfg(feature = "tokio-02")]
 mod tokio_02_ext;
-#[cfg(feature = "futures-io")]
+#[cfg(feature = "tokio")]
+mod tokio_ext;
+#[cfg(any(feature = "futures-io", feature = "tokio-02", feature = "tokio"))]
 mod track_closed;
 
 use proptest_derive::Arbitrary;
@@ -54,7 +56,7 @@ impl InputStream {
     }
 
     #[cfg(feature = "tokio-02")]
-    pub fn tokio_reader(&self) -> impl tokio_02::io::AsyncBufRead {
+    pub fn tokio_02_reader(&self) -> impl tokio_02::io::AsyncBufRead {
         use bytes::Bytes;
         use futures_test::stream::StreamTestExt;
         // TODO: By using the stream here we ensure that each chunk will require a separate
@@ -73,6 +75,26 @@ impl InputStream {
         )
     }
 
+    #[cfg(feature = "tokio")]
+    pub fn tokio_reader(&self) -> impl tokio::io::AsyncBufRead {
+        use bytes_1::Bytes;
+        use futures_test::stream::StreamTestExt;
+        // TODO: By using the stream here we ensure that each chunk will require a separate
+        // read/poll_fill_buf call to process to help test reading multiple chunks.
+        tokio_util::io::StreamReader::new(
+            futures::stream::iter(
+                self.0
+                    .clone()
+                    .into_iter()
+                    .map(Bytes::from)
+                    .flat_map(|bytes| vec![Bytes::new(), bytes])
+                    .chain(Some(Bytes::new()))
+                    .map(Ok::<_, std::io::Error>),
+            )
+            .interleave_pending(),
+        )
+    }
+
     pub fn bytes(&self) -> Vec<u8> {
         self.0.iter().flatten().cloned().collect()
     }
@@ -95,7 +117,7 @@ impl From<Vec<Vec<u8>>> for InputStream {
 }
 
 pub mod prelude {
-    pub use async_compression::Level;
+    pub use async_compression::{Level, LimitExceeded};
     #[cfg(feature = "stream")]
     pub use bytes::Bytes;
     #[cfg(feature = "futures-io")]
@@ -110,11 +132,19 @@ pub mod prelude {
         io::{self, Read},
         pin::Pin,
     };
+    #[cfg(feature = "tokio")]
+    pub use tokio::io::{
+        AsyncBufRead as TokioBufRead, AsyncRead as TokioRead, AsyncWrite as TokioWrite,
+    };
     #[cfg(feature = "tokio-02")]
     pub use tokio_02::io::{
-        AsyncBufRead as TokioBufRead, AsyncRead as TokioRead, AsyncWrite as TokioWrite,
+        AsyncBufRead as Tokio02BufRead, AsyncRead as Tokio02Read, AsyncWrite as Tokio02Write,
     };
 
+    pub fn limit_exceeded(err: &io::Error) -> Option<async_compression::LimitExceeded> {
+        err.get_ref()?.downcast_ref().copied()
+    }
+
     pub fn read_to_vec(mut read: impl Read) -> Vec<u8> {
         let mut output = vec![];
         read.read_to_end(&mut output).unwrap();
@@ -179,7 +209,7 @@ pub mod prelude {
     }
 
     #[cfg(feature = "tokio-02")]
-    pub fn tokio_read_to_vec(read: impl TokioRead) -> Vec<u8> {
+    pub fn tokio_02_read_to_vec(read: impl Tokio02Read) -> Vec<u8> {
         let mut output = std::io::Cursor::new(vec![0; 102_400]);
         pin_mut!(read);
         let len = block_on(crate::utils::tokio_02_ext::copy_buf(
@@ -193,6 +223,52 @@ pub mod prelude {
     }
 
     #[cfg(feature = "tokio-02")]
+    pub fn tokio_02_write_to_vec(
+        input: &[Vec<u8>],
+        create_writer: impl for<'a> FnOnce(
+            &'a mut (dyn Tokio02Write + Unpin),
+        ) -> Pin<Box<dyn Tokio02Write + 'a>>,
+        limit: usize,
+    ) -> Vec<u8> {
+        use crate::utils::tokio_02_ext::AsyncWriteTestExt;
+        use crate::utils::track_closed::TrackClosed;
+        use tokio_02::io::AsyncWriteExt as _;
+
+        let mut output = std::io::Cursor::new(Vec::new());
+        {
+            let mut test_writer = TrackClosed::new(
+                (&mut output)
+                    .limited_write(limit)
+                    .interleave_pending_write(),
+            );
+            {
+                let mut writer = create_writer(&mut test_writer);
+                for chunk in input {
+                    block_on(writer.write_all(chunk)).unwrap();
+                    block_on(writer.flush()).unwrap();
+                }
+                block_on(writer.shutdown()).unwrap();
+            }
+            assert!(test_writer.is_closed());
+        }
+        output.into_inner()
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn tokio_read_to_vec(read: impl TokioRead) -> Vec<u8> {
+        let mut output = std::io::Cursor::new(vec![0; 102_400]);
+        pin_mut!(read);
+        let len = block_on(crate::utils::tokio_ext::copy_buf(
+            tokio::io::BufReader::with_capacity(2, read),
+            &mut output,
+        ))
+        .unwrap();
+        let mut output = output.into_inner();
+        output.truncate(len as usize);
+        output
+    }
+
+    #[cfg(feature = "tokio")]
     pub fn tokio_write_to_vec(
         input: &[Vec<u8>],
         create_writer: impl for<'a> FnOnce(
@@ -200,9 +276,9 @@ pub mod prelude {
         ) -> Pin<Box<dyn TokioWrite + 'a>>,
         limit: usize,
     ) -> Vec<u8> {
-        use crate::utils::tokio_02_ext::AsyncWriteTestExt;
+        use crate::utils::tokio_ext::AsyncWriteTestExt;
         use crate::utils::track_closed::TrackClosed;
-        use tokio_02::io::AsyncWriteExt as _;
+        use tokio::io::AsyncWriteExt as _;
 
         let mut output = std::io::Cursor::new(Vec::new());
         {
@@ -225,6 +301,93 @@ pub mod prelude {
     }
 }
 
+/// Synchronous raw LZMA coding with liblzma, which the `xz2` bindings don't expose.
+#[cfg(any(feature = "lzip", feature = "lzma2"))]
+pub mod lzma_raw {
+    use lzma_sys::{
+        lzma_filter, lzma_lzma_preset, lzma_options_lzma, lzma_raw_buffer_decode,
+        lzma_raw_buffer_encode, lzma_vli, LZMA_BUF_ERROR, LZMA_OK, LZMA_VLI_UNKNOWN,
+    };
+    pub use lzma_sys::{LZMA_FILTER_LZMA1, LZMA_FILTER_LZMA2};
+    use std::ptr;
+
+    fn options(preset: u32, dict_size: Option<u32>) -> lzma_options_lzma {
+        let mut options: lzma_options_lzma = unsafe { std::mem::zeroed() };
+        assert_eq!(unsafe { lzma_lzma_preset(&mut options, preset) }, 0);
+        if let Some(dict_size) = dict_size {
+            options.dict_size = dict_size;
+        }
+        options
+    }
+
+    fn filters(id: lzma_vli, options: &mut lzma_options_lzma) -> [lzma_filter; 2] {
+        [
+            lzma_filter {
+                id,
+                options: options as *mut _ as *mut _,
+            },
+            lzma_filter {
+                id: LZMA_VLI_UNKNOWN,
+                options: ptr::null_mut(),
+            },
+        ]
+    }
+
+    /// Compresses `bytes` with the options of `preset`.
+    pub fn encode(id: lzma_vli, preset: u32, bytes: &[u8]) -> Vec<u8> {
+        let mut options = options(preset, None);
+        let filters = filters(id, &mut options);
+        let mut output = vec![0; bytes.len() + bytes.len() / 2 + 1024];
+        let mut out_pos = 0;
+        let ret = unsafe {
+            lzma_raw_buffer_encode(
+                filters.as_ptr(),
+                ptr::null(),
+                bytes.as_ptr(),
+                bytes.len(),
+                output.as_mut_ptr(),
+                &mut out_pos,
+                output.len(),
+            )
+        };
+        assert_eq!(ret, LZMA_OK);
+        output.truncate(out_pos);
+        output
+    }
+
+    /// Decompresses the stream at the start of `bytes`, returning the output and the length of
+    /// the compressed stream.
+    pub fn decode(id: lzma_vli, dict_size: u32, bytes: &[u8]) -> (Vec<u8>, usize) {
+        let mut options = options(0, Some(dict_size));
+        let filters = filters(id, &mut options);
+        let mut output = vec![0; 4096];
+        loop {
+            let (mut in_pos, mut out_pos) = (0, 0);
+            let ret = unsafe {
+                lzma_raw_buffer_decode(
+                    filters.as_ptr(),
+                    ptr::null(),
+                    bytes.as_ptr(),
+                    &mut in_pos,
+                    bytes.len(),
+                    output.as_mut_ptr(),
+                    &mut out_pos,
+                    output.len(),
+                )
+            };
+            match ret {
+                LZMA_OK => {
+                    output.truncate(out_pos);
+                    return (output, in_pos);
+                }
+                // The output was too small, or the input is truncated
+                LZMA_BUF_ERROR if output.len() < 1 << 28 => output.resize(output.len() * 2, 0),
+                ret => panic!("lzma error {}", ret),
+            }
+        }
+    }
+}
+
 macro_rules! algos {
     ($(pub mod $name:ident($feat:literal, $encoder:ident, $decoder:ident) { pub mod sync { $($tt:tt)* } })*) => {
         $(
@@ -295,6 +458,45 @@ macro_rules! algos {
                             $decoder as Decoder, $encoder as Encoder,
                         };
 
+                        pub fn compress(input: impl Tokio02BufRead) -> Vec<u8> {
+                            pin_mut!(input);
+                            tokio_02_read_to_vec(Encoder::with_quality(input, Level::Fastest))
+                        }
+
+                        pub fn decompress(input: impl Tokio02BufRead) -> Vec<u8> {
+                            pin_mut!(input);
+                            tokio_02_read_to_vec(Decoder::new(input))
+                        }
+                    }
+
+                    pub mod write {
+                        use crate::utils::prelude::*;
+                        pub use async_compression::tokio_02::write::{
+                            $decoder as Decoder, $encoder as Encoder,
+                        };
+
+                        pub fn compress(input: &[Vec<u8>], limit: usize) -> Vec<u8> {
+                            tokio_02_write_to_vec(
+                                input,
+                                |input| Box::pin(Encoder::with_quality(input, Level::Fastest)),
+                                limit,
+                            )
+                        }
+
+                        pub fn decompress(input: &[Vec<u8>], limit: usize) -> Vec<u8> {
+                            tokio_02_write_to_vec(input, |input| Box::pin(Decoder::new(input)), limit)
+                        }
+                    }
+                }
+
+                #[cfg(feature = "tokio")]
+                pub mod tokio {
+                    pub mod bufread {
+                        use crate::utils::prelude::*;
+                        pub use async_compression::tokio::bufread::{
+                            $decoder as Decoder, $encoder as Encoder,
+                        };
+
                         pub fn compress(input: impl TokioBufRead) -> Vec<u8> {
                             pin_mut!(input);
                             tokio_read_to_vec(Encoder::with_quality(input, Level::Fastest))
@@ -308,7 +510,7 @@ macro_rules! algos {
 
                     pub mod write {
                         use crate::utils::prelude::*;
-                        pub use async_compression::tokio_02::write::{
+                        pub use async_compression::tokio::write::{
                             $decoder as Decoder, $encoder as Encoder,
                         };
 
@@ -448,6 +650,26 @@ algos! {
         }
     }
 
+    pub mod lz4("lz4", Lz4Encoder, Lz4Decoder) {
+        pub mod sync {
+            use crate::utils::prelude::*;
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                use std::io::Write;
+
+                let mut encoder = lz4::EncoderBuilder::new().build(Vec::new()).unwrap();
+                encoder.write_all(bytes).unwrap();
+                let (output, result) = encoder.finish();
+                result.unwrap();
+                output
+            }
+
+            pub fn decompress(bytes: &[u8]) -> Vec<u8> {
+                read_to_vec(lz4::Decoder::new(bytes).unwrap())
+            }
+        }
+    }
+
     pub mod lzma("lzma", LzmaEncoder, LzmaDecoder) {
         pub mod sync {
             use crate::utils::prelude::*;
@@ -473,6 +695,68 @@ algos! {
             }
         }
     }
+
+    pub mod lzip("lzip", LzipEncoder, LzipDecoder) {
+        pub mod sync {
+            use crate::utils::lzma_raw;
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                let mut crc = crc32fast::Hasher::new();
+                crc.update(bytes);
+
+                // Preset 0 uses a 256 KiB dictionary
+                let mut output = b"LZIP\x01\x12".to_vec();
+                output.extend(lzma_raw::encode(lzma_raw::LZMA_FILTER_LZMA1, 0, bytes));
+                output.extend(&crc.finalize().to_le_bytes());
+                output.extend(&(bytes.len() as u64).to_le_bytes());
+                let member_size = output.len() as u64 + 8;
+                output.extend(&member_size.to_le_bytes());
+                output
+            }
+
+            pub fn decompress(mut bytes: &[u8]) -> Vec<u8> {
+                let mut output = Vec::new();
+
+                while !bytes.is_empty() {
+                    assert_eq!(&bytes[..5], b"LZIP\x01");
+                    let base = 1 << (bytes[5] & 0x1f);
+                    let dict_size = base - base / 16 * u32::from(bytes[5] >> 5);
+
+                    let (data, len) =
+                        lzma_raw::decode(lzma_raw::LZMA_FILTER_LZMA1, dict_size, &bytes[6..]);
+                    let (trailer, rest) = bytes[6 + len..].split_at(20);
+
+                    let mut crc = crc32fast::Hasher::new();
+                    crc.update(&data);
+                    assert_eq!(trailer[..4], crc.finalize().to_le_bytes());
+                    assert_eq!(trailer[4..12], (data.len() as u64).to_le_bytes());
+                    assert_eq!(trailer[12..], (len as u64 + 26).to_le_bytes());
+
+                    output.extend(data);
+                    bytes = rest;
+                }
+
+                output
+            }
+        }
+    }
+
+    pub mod lzma2("lzma2", Lzma2Encoder, Lzma2Decoder) {
+        pub mod sync {
+            use crate::utils::lzma_raw;
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                lzma_raw::encode(lzma_raw::LZMA_FILTER_LZMA2, 0, bytes)
+            }
+
+            pub fn decompress(bytes: &[u8]) -> Vec<u8> {
+                // Large enough for every preset
+                let (output, len) = lzma_raw::decode(lzma_raw::LZMA_FILTER_LZMA2, 64 << 20, bytes);
+                assert_eq!(len, bytes.len());
+                output
+            }
+        }
+    }
 }
 
 macro_rules! test_cases {
@@ -576,10 +860,62 @@ macro_rules! test_cases {
                 }
 
                 mod decompress {
-                    use crate::utils;
+                    use crate::utils::{self, prelude::*};
                     use futures::{executor::block_on, stream::StreamExt as _};
                     use std::iter::FromIterator;
 
+                    #[test]
+                    #[ntest::timeout(1000)]
+                    fn max_output_size() {
+                        let input: Vec<u8> = vec![7; 10_000];
+                        let compressed = utils::$variant::sync::compress(&input);
+                        let stream = utils::InputStream::from(vec![compressed]);
+                        let mut decoder = utils::$variant::stream::Decoder::new(stream.stream());
+                        decoder.max_output_size(Some(10_000));
+                        let output = stream_to_vec(decoder);
+
+                        assert_eq!(output, input);
+                    }
+
+                    #[test]
+                    #[ntest::timeout(1000)]
+                    fn max_output_size_exceeded() {
+                        let input: Vec<u8> = vec![7; 10_000];
+                        let compressed = utils::$variant::sync::compress(&input);
+                        let stream = utils::InputStream::from(vec![compressed]);
+                        let mut decoder = utils::$variant::stream::Decoder::new(stream.stream());
+                        decoder.max_output_size(Some(9_999));
+                        let err = block_on_stream(decoder).find_map(Result::err).unwrap();
+
+                        assert_eq!(limit_exceeded(&err), Some(LimitExceeded::OutputSize(9_999)));
+                    }
+
+                    #[test]
+                    #[ntest::timeout(1000)]
+                    fn max_ratio() {
+                        let input: Vec<u8> = Vec::from_iter((0..10_000).map(|_| rand::random()));
+                        let compressed = utils::$variant::sync::compress(&input);
+                        let stream = utils::InputStream::from(vec![compressed]);
+                        let mut decoder = utils::$variant::stream::Decoder::new(stream.stream());
+                        decoder.max_ratio(Some(2));
+                        let output = stream_to_vec(decoder);
+
+                        assert_eq!(output, input);
+                    }
+
+                    #[test]
+                    #[ntest::timeout(1000)]
+                    fn max_ratio_exceeded() {
+                        let input: Vec<u8> = vec![7; 10_000];
+                        let compressed = utils::$variant::sync::compress(&input);
+                        let stream = utils::InputStream::from(vec![compressed]);
+                        let mut decoder = utils::$variant::stream::Decoder::new(stream.stream());
+                        decoder.max_ratio(Some(10));
+                        let err = block_on_stream(decoder).find_map(Result::err).unwrap();
+
+                        assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                    }
+
                     #[test]
                     #[ntest::timeout(1000)]
                     fn empty() {
@@ -803,6 +1139,84 @@ macro_rules! test_cases {
                         use crate::utils::{self, prelude::*};
                         use std::iter::FromIterator;
 
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder =
+                                utils::$variant::futures::bufread::Decoder::new(stream.reader());
+                            decoder.max_output_size(Some(10_000));
+                            let mut output = Vec::new();
+                            block_on(futures::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder =
+                                utils::$variant::futures::bufread::Decoder::new(stream.reader());
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(futures::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
+                            );
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder =
+                                utils::$variant::futures::bufread::Decoder::new(stream.reader());
+                            decoder.max_ratio(Some(2));
+                            let mut output = Vec::new();
+                            block_on(futures::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder =
+                                utils::$variant::futures::bufread::Decoder::new(stream.reader());
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(futures::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
                         #[test]
                         #[ntest::timeout(1000)]
                         fn empty() {
@@ -916,6 +1330,26 @@ macro_rules! test_cases {
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
                         }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn trailing_data() {
+                            use futures::io::AsyncReadExt;
+
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                b"trailing".to_vec(),
+                            ]
+                            .concat();
+
+                            let mut decoder =
+                                utils::$variant::futures::bufread::Decoder::new(&compressed[..]);
+                            let mut output = Vec::new();
+                            block_on(decoder.read_to_end(&mut output)).unwrap();
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                            assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+                        }
                     }
                 }
 
@@ -1047,41 +1481,117 @@ macro_rules! test_cases {
                     }
 
                     mod decompress {
-                        use crate::utils;
+                        use crate::utils::{self, prelude::*};
                         use std::iter::FromIterator;
 
                         #[test]
                         #[ntest::timeout(1000)]
-                        fn empty() {
-                            let compressed = utils::$variant::sync::compress(&[]);
-
-                            let stream = utils::InputStream::from(vec![compressed]);
-                            let output = utils::$variant::futures::write::decompress(
-                                stream.as_ref(),
-                                65_536,
-                            );
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::futures::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(10_000));
+                            block_on(futures::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(futures::io::AsyncWriteExt::close(&mut decoder)).unwrap();
 
-                            assert_eq!(output, &[][..]);
+                            assert_eq!(decoder.into_inner(), input);
                         }
 
                         #[test]
                         #[ntest::timeout(1000)]
-                        fn zeros() {
-                            let compressed = utils::$variant::sync::compress(&[0; 10]);
-
-                            let stream = utils::InputStream::from(vec![compressed]);
-                            let output = utils::$variant::futures::write::decompress(
-                                stream.as_ref(),
-                                65_536,
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::futures::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(async {
+                                futures::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                futures::io::AsyncWriteExt::close(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
                             );
-
-                            assert_eq!(output, &[0; 10][..]);
                         }
 
                         #[test]
                         #[ntest::timeout(1000)]
-                        fn short() {
-                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::futures::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(2));
+                            block_on(futures::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(futures::io::AsyncWriteExt::close(&mut decoder)).unwrap();
+
+                            assert_eq!(decoder.into_inner(), input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::futures::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(async {
+                                futures::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                futures::io::AsyncWriteExt::close(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty() {
+                            let compressed = utils::$variant::sync::compress(&[]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output = utils::$variant::futures::write::decompress(
+                                stream.as_ref(),
+                                65_536,
+                            );
+
+                            assert_eq!(output, &[][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn zeros() {
+                            let compressed = utils::$variant::sync::compress(&[0; 10]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output = utils::$variant::futures::write::decompress(
+                                stream.as_ref(),
+                                65_536,
+                            );
+
+                            assert_eq!(output, &[0; 10][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short() {
+                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
 
                             let stream = utils::InputStream::from(vec![compressed]);
                             let output = utils::$variant::futures::write::decompress(
@@ -1139,6 +1649,55 @@ macro_rules! test_cases {
 
                             assert_eq!(output, input);
                         }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let output = utils::prelude::async_write_to_vec(
+                                &[compressed],
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::futures::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                65_536,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members_chunked() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output = utils::prelude::async_write_to_vec(
+                                stream.as_ref(),
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::futures::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                2,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
                     }
                 }
             }
@@ -1166,8 +1725,9 @@ macro_rules! test_cases {
                         fn empty_chunk() {
                             let input = utils::InputStream::from(vec![vec![]]);
 
-                            let compressed =
-                                utils::$variant::tokio_02::bufread::compress(input.tokio_reader());
+                            let compressed = utils::$variant::tokio_02::bufread::compress(
+                                input.tokio_02_reader(),
+                            );
                             let output = utils::$variant::sync::decompress(&compressed);
 
                             assert_eq!(output, input.bytes());
@@ -1178,8 +1738,9 @@ macro_rules! test_cases {
                         fn short() {
                             let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
 
-                            let compressed =
-                                utils::$variant::tokio_02::bufread::compress(input.tokio_reader());
+                            let compressed = utils::$variant::tokio_02::bufread::compress(
+                                input.tokio_02_reader(),
+                            );
                             let output = utils::$variant::sync::decompress(&compressed);
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
@@ -1194,8 +1755,9 @@ macro_rules! test_cases {
                             ];
                             let input = utils::InputStream::from(input);
 
-                            let compressed =
-                                utils::$variant::tokio_02::bufread::compress(input.tokio_reader());
+                            let compressed = utils::$variant::tokio_02::bufread::compress(
+                                input.tokio_02_reader(),
+                            );
                             let output = utils::$variant::sync::decompress(&compressed);
 
                             assert_eq!(output, input.bytes());
@@ -1206,10 +1768,10 @@ macro_rules! test_cases {
                             let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
 
                             let encoder = utils::$variant::tokio_02::bufread::Encoder::with_quality(
-                                input.tokio_reader(),
+                                input.tokio_02_reader(),
                                 Level::Precise(0),
                             );
-                            let compressed = tokio_read_to_vec(encoder);
+                            let compressed = tokio_02_read_to_vec(encoder);
                             let output = utils::$variant::sync::decompress(&compressed);
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
@@ -1220,10 +1782,10 @@ macro_rules! test_cases {
                             let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
 
                             let encoder = utils::$variant::tokio_02::bufread::Encoder::with_quality(
-                                input.tokio_reader(),
+                                input.tokio_02_reader(),
                                 Level::Precise(u32::max_value()),
                             );
-                            let compressed = tokio_read_to_vec(encoder);
+                            let compressed = tokio_02_read_to_vec(encoder);
                             let output = utils::$variant::sync::decompress(&compressed);
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
@@ -1234,6 +1796,88 @@ macro_rules! test_cases {
                         use crate::utils::{self, prelude::*};
                         use std::iter::FromIterator;
 
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio_02::bufread::Decoder::new(
+                                stream.tokio_02_reader(),
+                            );
+                            decoder.max_output_size(Some(10_000));
+                            let mut output = Vec::new();
+                            block_on(tokio_02::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio_02::bufread::Decoder::new(
+                                stream.tokio_02_reader(),
+                            );
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(tokio_02::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
+                            );
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio_02::bufread::Decoder::new(
+                                stream.tokio_02_reader(),
+                            );
+                            decoder.max_ratio(Some(2));
+                            let mut output = Vec::new();
+                            block_on(tokio_02::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio_02::bufread::Decoder::new(
+                                stream.tokio_02_reader(),
+                            );
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(tokio_02::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
                         #[test]
                         #[ntest::timeout(1000)]
                         fn empty() {
@@ -1241,7 +1885,7 @@ macro_rules! test_cases {
 
                             let stream = utils::InputStream::from(vec![compressed]);
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, &[][..]);
@@ -1254,7 +1898,7 @@ macro_rules! test_cases {
 
                             let stream = utils::InputStream::from(vec![compressed]);
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, &[0; 10][..]);
@@ -1267,7 +1911,7 @@ macro_rules! test_cases {
 
                             let stream = utils::InputStream::from(vec![compressed]);
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
@@ -1282,7 +1926,7 @@ macro_rules! test_cases {
                                 compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
                             );
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
@@ -1297,10 +1941,10 @@ macro_rules! test_cases {
                             compressed.extend_from_slice(&[7, 8, 9, 10]);
 
                             let stream = utils::InputStream::from(vec![compressed]);
-                            let mut reader = stream.tokio_reader();
+                            let mut reader = stream.tokio_02_reader();
                             let output =
                                 utils::$variant::tokio_02::bufread::decompress(&mut reader);
-                            let trailer = tokio_read_to_vec(reader);
+                            let trailer = tokio_02_read_to_vec(reader);
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
                             assert_eq!(trailer, &[7, 8, 9, 10][..]);
@@ -1314,7 +1958,7 @@ macro_rules! test_cases {
 
                             let stream = utils::InputStream::from(vec![compressed]);
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, input);
@@ -1330,7 +1974,7 @@ macro_rules! test_cases {
                                 compressed.chunks(1024).map(Vec::from).collect::<Vec<_>>(),
                             );
                             let output = utils::$variant::tokio_02::bufread::decompress(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
 
                             assert_eq!(output, input);
@@ -1348,13 +1992,33 @@ macro_rules! test_cases {
                             let stream = utils::InputStream::from(vec![compressed]);
 
                             let mut decoder = utils::$variant::tokio_02::bufread::Decoder::new(
-                                stream.tokio_reader(),
+                                stream.tokio_02_reader(),
                             );
                             decoder.multiple_members(true);
-                            let output = utils::prelude::tokio_read_to_vec(decoder);
+                            let output = utils::prelude::tokio_02_read_to_vec(decoder);
 
                             assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
                         }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn trailing_data() {
+                            use tokio_02::io::AsyncReadExt;
+
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                b"trailing".to_vec(),
+                            ]
+                            .concat();
+
+                            let mut decoder =
+                                utils::$variant::tokio_02::bufread::Decoder::new(&compressed[..]);
+                            let mut output = Vec::new();
+                            block_on(decoder.read_to_end(&mut output)).unwrap();
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                            assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+                        }
                     }
                 }
 
@@ -1446,7 +2110,7 @@ macro_rules! test_cases {
                         fn with_level_0() {
                             let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
 
-                            let compressed = tokio_write_to_vec(
+                            let compressed = tokio_02_write_to_vec(
                                 input.as_ref(),
                                 |input| {
                                     Box::pin(
@@ -1467,7 +2131,7 @@ macro_rules! test_cases {
                         fn with_level_max() {
                             let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
 
-                            let compressed = tokio_write_to_vec(
+                            let compressed = tokio_02_write_to_vec(
                                 input.as_ref(),
                                 |input| {
                                     Box::pin(
@@ -1486,9 +2150,85 @@ macro_rules! test_cases {
                     }
 
                     mod decompress {
-                        use crate::utils;
+                        use crate::utils::{self, prelude::*};
                         use std::iter::FromIterator;
 
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio_02::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(10_000));
+                            block_on(tokio_02::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(tokio_02::io::AsyncWriteExt::shutdown(&mut decoder)).unwrap();
+
+                            assert_eq!(decoder.into_inner(), input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio_02::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(async {
+                                tokio_02::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                tokio_02::io::AsyncWriteExt::shutdown(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
+                            );
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio_02::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(2));
+                            block_on(tokio_02::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(tokio_02::io::AsyncWriteExt::shutdown(&mut decoder)).unwrap();
+
+                            assert_eq!(decoder.into_inner(), input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio_02::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(async {
+                                tokio_02::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                tokio_02::io::AsyncWriteExt::shutdown(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
                         #[test]
                         #[ntest::timeout(1000)]
                         fn empty() {
@@ -1578,6 +2318,697 @@ macro_rules! test_cases {
 
                             assert_eq!(output, input);
                         }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let output = utils::prelude::tokio_02_write_to_vec(
+                                &[compressed],
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::tokio_02::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                65_536,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members_chunked() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output = utils::prelude::tokio_02_write_to_vec(
+                                stream.as_ref(),
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::tokio_02::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                2,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
+                    }
+                }
+            }
+
+            #[cfg(feature = "tokio")]
+            mod tokio {
+                mod bufread {
+                    mod compress {
+                        use crate::utils::{self, prelude::*};
+                        use std::iter::FromIterator;
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty() {
+                            let mut input: &[u8] = &[];
+                            let compressed = utils::$variant::tokio::bufread::compress(&mut input);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty_chunk() {
+                            let input = utils::InputStream::from(vec![vec![]]);
+
+                            let compressed =
+                                utils::$variant::tokio::bufread::compress(input.tokio_reader());
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, input.bytes());
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let compressed =
+                                utils::$variant::tokio::bufread::compress(input.tokio_reader());
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long() {
+                            let input = vec![
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                            ];
+                            let input = utils::InputStream::from(input);
+
+                            let compressed =
+                                utils::$variant::tokio::bufread::compress(input.tokio_reader());
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, input.bytes());
+                        }
+
+                        #[test]
+                        fn with_level_0() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let encoder = utils::$variant::tokio::bufread::Encoder::with_quality(
+                                input.tokio_reader(),
+                                Level::Precise(0),
+                            );
+                            let compressed = tokio_read_to_vec(encoder);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        fn with_level_max() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let encoder = utils::$variant::tokio::bufread::Encoder::with_quality(
+                                input.tokio_reader(),
+                                Level::Precise(u32::max_value()),
+                            );
+                            let compressed = tokio_read_to_vec(encoder);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+                    }
+
+                    mod decompress {
+                        use crate::utils::{self, prelude::*};
+                        use std::iter::FromIterator;
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio::bufread::Decoder::new(
+                                stream.tokio_reader(),
+                            );
+                            decoder.max_output_size(Some(10_000));
+                            let mut output = Vec::new();
+                            block_on(tokio::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio::bufread::Decoder::new(
+                                stream.tokio_reader(),
+                            );
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(tokio::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
+                            );
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio::bufread::Decoder::new(
+                                stream.tokio_reader(),
+                            );
+                            decoder.max_ratio(Some(2));
+                            let mut output = Vec::new();
+                            block_on(tokio::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut output,
+                            ))
+                            .unwrap();
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut decoder = utils::$variant::tokio::bufread::Decoder::new(
+                                stream.tokio_reader(),
+                            );
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(tokio::io::AsyncReadExt::read_to_end(
+                                &mut decoder,
+                                &mut Vec::new(),
+                            ))
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty() {
+                            let compressed = utils::$variant::sync::compress(&[]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, &[][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn zeros() {
+                            let compressed = utils::$variant::sync::compress(&[0; 10]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, &[0; 10][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short() {
+                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short_chunks() {
+                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn trailer() {
+                            let mut compressed =
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+                            compressed.extend_from_slice(&[7, 8, 9, 10]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let mut reader = stream.tokio_reader();
+                            let output = utils::$variant::tokio::bufread::decompress(&mut reader);
+                            let trailer = tokio_read_to_vec(reader);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                            assert_eq!(trailer, &[7, 8, 9, 10][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long() {
+                            let input = Vec::from_iter((0..65_536).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long_chunks() {
+                            let input = Vec::from_iter((0..65_536).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(1024).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output =
+                                utils::$variant::tokio::bufread::decompress(stream.tokio_reader());
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+
+                            let mut decoder = utils::$variant::tokio::bufread::Decoder::new(
+                                stream.tokio_reader(),
+                            );
+                            decoder.multiple_members(true);
+                            let output = utils::prelude::tokio_read_to_vec(decoder);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn trailing_data() {
+                            use tokio::io::AsyncReadExt;
+
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                b"trailing".to_vec(),
+                            ]
+                            .concat();
+
+                            let mut decoder =
+                                utils::$variant::tokio::bufread::Decoder::new(&compressed[..]);
+                            let mut output = Vec::new();
+                            block_on(decoder.read_to_end(&mut output)).unwrap();
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                            assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+                        }
+                    }
+                }
+
+                mod write {
+                    mod compress {
+                        use crate::utils::{self, prelude::*};
+                        use std::iter::FromIterator;
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty() {
+                            let input = utils::InputStream::from(vec![]);
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 65_536);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty_chunk() {
+                            let input = utils::InputStream::from(vec![vec![]]);
+
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 65_536);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, input.bytes());
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 65_536);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short_chunk_output() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 2);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long() {
+                            let input = vec![
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                            ];
+                            let input = utils::InputStream::from(input);
+
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 65_536);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, input.bytes());
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long_chunk_output() {
+                            let input = vec![
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                                Vec::from_iter((0..32_768).map(|_| rand::random())),
+                            ];
+                            let input = utils::InputStream::from(input);
+
+                            let compressed =
+                                utils::$variant::tokio::write::compress(input.as_ref(), 20);
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, input.bytes());
+                        }
+
+                        #[test]
+                        fn with_level_0() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let compressed = tokio_write_to_vec(
+                                input.as_ref(),
+                                |input| {
+                                    Box::pin(utils::$variant::tokio::write::Encoder::with_quality(
+                                        input,
+                                        Level::Precise(0),
+                                    ))
+                                },
+                                65_536,
+                            );
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        fn with_level_max() {
+                            let input = utils::InputStream::from([[1, 2, 3], [4, 5, 6]]);
+
+                            let compressed = tokio_write_to_vec(
+                                input.as_ref(),
+                                |input| {
+                                    Box::pin(utils::$variant::tokio::write::Encoder::with_quality(
+                                        input,
+                                        Level::Precise(u32::max_value()),
+                                    ))
+                                },
+                                65_536,
+                            );
+                            let output = utils::$variant::sync::decompress(&compressed);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+                    }
+
+                    mod decompress {
+                        use crate::utils::{self, prelude::*};
+                        use std::iter::FromIterator;
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(10_000));
+                            block_on(tokio::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(tokio::io::AsyncWriteExt::shutdown(&mut decoder)).unwrap();
+
+                            assert_eq!(decoder.into_inner(), input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_output_size_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio::write::Decoder::new(Vec::new());
+                            decoder.max_output_size(Some(9_999));
+                            let err = block_on(async {
+                                tokio::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                tokio::io::AsyncWriteExt::shutdown(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(
+                                limit_exceeded(&err),
+                                Some(LimitExceeded::OutputSize(9_999))
+                            );
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio() {
+                            let input: Vec<u8> =
+                                Vec::from_iter((0..10_000).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(2));
+                            block_on(tokio::io::AsyncWriteExt::write_all(
+                                &mut decoder,
+                                &compressed,
+                            ))
+                            .unwrap();
+                            block_on(tokio::io::AsyncWriteExt::shutdown(&mut decoder)).unwrap();
+
+                            assert_eq!(decoder.into_inner(), input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn max_ratio_exceeded() {
+                            let input: Vec<u8> = vec![7; 10_000];
+                            let compressed = utils::$variant::sync::compress(&input);
+                            let mut decoder =
+                                utils::$variant::tokio::write::Decoder::new(Vec::new());
+                            decoder.max_ratio(Some(10));
+                            let err = block_on(async {
+                                tokio::io::AsyncWriteExt::write_all(&mut decoder, &compressed)
+                                    .await?;
+                                tokio::io::AsyncWriteExt::shutdown(&mut decoder).await
+                            })
+                            .unwrap_err();
+
+                            assert_eq!(limit_exceeded(&err), Some(LimitExceeded::Ratio(10)));
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn empty() {
+                            let compressed = utils::$variant::sync::compress(&[]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, &[][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn zeros() {
+                            let compressed = utils::$variant::sync::compress(&[0; 10]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, &[0; 10][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short() {
+                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn short_chunks() {
+                            let compressed = utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long() {
+                            let input = Vec::from_iter((0..65_536).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+
+                            let stream = utils::InputStream::from(vec![compressed]);
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn long_chunks() {
+                            let input = Vec::from_iter((0..65_536).map(|_| rand::random()));
+                            let compressed = utils::$variant::sync::compress(&input);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(1024).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output =
+                                utils::$variant::tokio::write::decompress(stream.as_ref(), 65_536);
+
+                            assert_eq!(output, input);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let output = utils::prelude::tokio_write_to_vec(
+                                &[compressed],
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::tokio::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                65_536,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
+
+                        #[test]
+                        #[ntest::timeout(1000)]
+                        fn multiple_members_chunked() {
+                            let compressed = [
+                                utils::$variant::sync::compress(&[1, 2, 3, 4, 5, 6]),
+                                utils::$variant::sync::compress(&[6, 5, 4, 3, 2, 1]),
+                            ]
+                            .join(&[][..]);
+
+                            let stream = utils::InputStream::from(
+                                compressed.chunks(2).map(Vec::from).collect::<Vec<_>>(),
+                            );
+                            let output = utils::prelude::tokio_write_to_vec(
+                                stream.as_ref(),
+                                |input| {
+                                    let mut decoder =
+                                        utils::$variant::tokio::write::Decoder::new(input);
+                                    decoder.multiple_members(true);
+                                    Box::pin(decoder)
+                                },
+                                2,
+                            );
+
+                            assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+                        }
                     }
                 }
             }
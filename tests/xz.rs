@@ -44,6 +44,11 @@ fn stream_multiple_members_with_invalid_padding() {
     let mut decoder = utils::xz::stream::Decoder::new(stream.stream());
     decoder.multiple_members(true);
 
+    // Each member's output is yielded separately, so the first is returned before the error
+    assert_eq!(
+        block_on(decoder.next()).unwrap().unwrap(),
+        &[1, 2, 3, 4, 5, 6][..]
+    );
     assert!(block_on(decoder.next()).unwrap().is_err());
     assert!(block_on(decoder.next()).is_none());
 }
@@ -89,3 +94,392 @@ fn bufread_multiple_members_with_invalid_padding() {
     let mut output = Vec::new();
     assert!(block_on(decoder.read_to_end(&mut output)).is_err());
 }
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-bufread")]
+fn bufread_stop_at_trailing_data_after_padding() {
+    let compressed = [
+        utils::xz::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        vec![0, 0, 0, 0],
+        utils::xz::sync::compress(&[6, 5, 4, 3, 2, 1]),
+        vec![0, 0, 0, 0],
+        b"trailing".to_vec(),
+    ]
+    .concat();
+
+    let mut decoder = utils::xz::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"\0\0\0\0trailing"[..]);
+}
+
+#[allow(unused)]
+fn multithreaded_input() -> Vec<u8> {
+    (0..300_000u32).map(|i| (i * 7 ^ i >> 9) as u8).collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn xz_stream_multithreaded() {
+    use async_compression::XzParams;
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    let params = XzParams::default().threads(4).block_size(64 * 1024);
+    let encoder = utils::xz::stream::Encoder::with_quality_and_params(
+        utils::InputStream::from(vec![input.clone()]).stream(),
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_write_multithreaded_flush() {
+    use async_compression::XzParams;
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    // Flushes after each chunk, which ends the current block
+    let compressed = async_write_to_vec(
+        &input.chunks(100_000).map(Vec::from).collect::<Vec<_>>(),
+        |output| {
+            Box::pin(
+                utils::xz::futures::write::Encoder::with_quality_and_params(
+                    output,
+                    Level::Fastest,
+                    XzParams::default().threads(2),
+                )
+                .unwrap(),
+            )
+        },
+        usize::MAX,
+    );
+
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_bufread_multithreaded_wakes_instead_of_blocking() {
+    use async_compression::XzParams;
+    use futures::{
+        io::AsyncRead,
+        task::{waker, ArcWake},
+    };
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+    use utils::prelude::*;
+
+    struct Wakes(AtomicUsize);
+
+    impl ArcWake for Wakes {
+        fn wake_by_ref(arc: &Arc<Self>) {
+            arc.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let input = multithreaded_input();
+    let wakes = Arc::new(Wakes(AtomicUsize::new(0)));
+    let waker = waker(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    // Finishing has to wait on the workers, which must yield the task rather than block it
+    let mut encoder = utils::xz::futures::bufread::Encoder::with_quality_and_params(
+        &input[..],
+        Level::Default,
+        XzParams::default().threads(2).block_size(16 * 1024),
+    )
+    .unwrap();
+    let mut compressed = Vec::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut pending = 0;
+    loop {
+        match Pin::new(&mut encoder).poll_read(&mut cx, &mut buffer) {
+            Poll::Ready(Ok(0)) => break,
+            Poll::Ready(Ok(len)) => compressed.extend_from_slice(&buffer[..len]),
+            Poll::Ready(Err(err)) => panic!("{}", err),
+            Poll::Pending => {
+                // Woken before returning, so the executor polls again
+                pending += 1;
+                assert!(wakes.0.load(Ordering::SeqCst) >= pending);
+            }
+        }
+    }
+
+    assert!(pending > 0);
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_invalid_params() {
+    use async_compression::XzParams;
+    use utils::prelude::*;
+
+    assert!(
+        utils::xz::futures::bufread::Encoder::with_quality_and_params(
+            &[][..],
+            Level::Default,
+            XzParams::default().threads(0),
+        )
+        .is_err()
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_max_memory_usage() {
+    use utils::prelude::*;
+
+    // The fastest preset uses a 256 KiB dictionary
+    let compressed = async_read_to_vec(utils::xz::futures::bufread::Encoder::with_quality(
+        &[1, 2, 3, 4, 5, 6][..],
+        Level::Fastest,
+    ));
+
+    let mut decoder = utils::xz::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.max_memory_usage(Some(64 * 1024));
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    match limit_exceeded(&err) {
+        Some(LimitExceeded::MemoryUsage { limit, required }) => {
+            assert_eq!(limit, 64 * 1024);
+            assert!(required > 256 * 1024 && required < 1024 * 1024);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    let mut decoder = utils::xz::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.max_memory_usage(Some(1024 * 1024));
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_max_memory_usage_mid_stream() {
+    use utils::prelude::*;
+
+    let input: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    let compressed = async_read_to_vec(utils::xz::futures::bufread::Encoder::with_quality(
+        &input[..],
+        Level::Fastest,
+    ));
+
+    let mut decoder = utils::xz::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.max_memory_usage(Some(1024 * 1024));
+    let mut output = vec![0; 1000];
+    block_on(decoder.read_exact(&mut output)).unwrap();
+    decoder.max_memory_usage(Some(2 * 1024 * 1024));
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, input);
+
+    let mut decoder = utils::xz::futures::bufread::Decoder::new(&compressed[..]);
+    let mut output = vec![0; 1000];
+    block_on(decoder.read_exact(&mut output)).unwrap();
+    decoder.max_memory_usage(Some(64 * 1024));
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    match limit_exceeded(&err) {
+        Some(LimitExceeded::MemoryUsage { limit, required }) => {
+            assert_eq!(limit, 64 * 1024);
+            assert!(required > 256 * 1024 && required < 1024 * 1024);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn xz_stream_check_and_filters() {
+    use async_compression::{XzCheck, XzFilter, XzMode, XzParams};
+    use utils::prelude::*;
+
+    // Looks a little like x86 code, with relative calls
+    let input: Vec<u8> = (0..20_000u32)
+        .flat_map(|i| [0x55, 0xe8, (i % 7) as u8, 0, 0, 0, 0x5d])
+        .collect();
+
+    let params = XzParams::default()
+        .check(XzCheck::Sha256)
+        .filter(XzFilter::X86)
+        .dict_size(64 * 1024)
+        .literal_context_bits(0)
+        .literal_position_bits(2)
+        .position_bits(2)
+        .nice_len(128)
+        .mode(XzMode::Normal);
+    let encoder = utils::xz::stream::Encoder::with_quality_and_params(
+        utils::InputStream::from(vec![input.clone()]).stream(),
+        Level::Default,
+        params,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    // The check type is the second stream flags byte after the 6 byte magic, and the block
+    // header that follows stores the number of filters minus one in its flags
+    assert_eq!(compressed[7], 0x0a);
+    assert_eq!(compressed[13] & 0x03, 1);
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn xz_stream_delta_filter() {
+    use async_compression::{XzFilter, XzParams};
+    use utils::prelude::*;
+
+    // Looks a little like 16-bit stereo audio, two slowly changing channels
+    let input: Vec<u8> = (0..50_000u32)
+        .flat_map(|i| {
+            let left = (i * 3) as u16;
+            let right = (i * 5) as u16;
+            [
+                left as u8,
+                (left >> 8) as u8,
+                right as u8,
+                (right >> 8) as u8,
+            ]
+        })
+        .collect();
+
+    let compress = |params| {
+        stream_to_vec(
+            utils::xz::stream::Encoder::with_quality_and_params(
+                utils::InputStream::from(vec![input.clone()]).stream(),
+                Level::Default,
+                params,
+            )
+            .unwrap(),
+        )
+    };
+    let compressed = compress(XzParams::default().filter(XzFilter::Delta { distance: 4 }));
+
+    // The block header after the 12 byte stream header starts with the delta filter id, then the
+    // size of its properties and the distance minus one
+    assert_eq!(compressed[14..17], [0x03, 1, 3]);
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+    assert!(compressed.len() < compress(XzParams::default().dict_size(1 << 20)).len());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_multithreaded_filters() {
+    use async_compression::{XzFilter, XzParams};
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    let params = XzParams::default()
+        .threads(2)
+        .block_size(64 * 1024)
+        .filter(XzFilter::Arm);
+    let encoder = utils::xz::futures::bufread::Encoder::with_quality_and_params(
+        &input[..],
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+    let compressed = async_read_to_vec(encoder);
+
+    assert_eq!(utils::xz::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn xz_futures_invalid_lzma2_options() {
+    use async_compression::{XzFilter, XzParams};
+    use utils::prelude::*;
+
+    let params = XzParams::default()
+        .literal_context_bits(4)
+        .literal_position_bits(4);
+    assert!(
+        utils::xz::futures::bufread::Encoder::with_quality_and_params(
+            &[][..],
+            Level::Default,
+            params
+        )
+        .is_err()
+    );
+
+    let params = XzParams::default()
+        .filter(XzFilter::X86)
+        .filter(XzFilter::X86)
+        .filter(XzFilter::X86)
+        .filter(XzFilter::X86);
+    assert!(
+        utils::xz::futures::bufread::Encoder::with_quality_and_params(
+            &[][..],
+            Level::Default,
+            params
+        )
+        .is_err()
+    );
+
+    let params = XzParams::default().filter(XzFilter::Delta { distance: 0 });
+    assert!(
+        utils::xz::futures::bufread::Encoder::with_quality_and_params(
+            &[][..],
+            Level::Default,
+            params
+        )
+        .is_err()
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn xz_tokio_write_filter_flush() {
+    use async_compression::{XzFilter, XzParams};
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+
+    // Flushes after each chunk, which has to end the block with a filter
+    let compressed = tokio_write_to_vec(
+        input.as_ref(),
+        |output| {
+            Box::pin(
+                utils::xz::tokio::write::Encoder::with_quality_and_params(
+                    output,
+                    Level::Default,
+                    XzParams::default().filter(XzFilter::Sparc),
+                )
+                .unwrap(),
+            )
+        },
+        16,
+    );
+
+    assert_eq!(utils::xz::sync::decompress(&compressed), input.bytes());
+}
@@ -2,3 +2,168 @@
 mod utils;
 
 test_cases!(zlib);
+
+#[allow(unused)]
+use async_compression::DictionaryRequired;
+
+/// A raw content dictionary, any bytes can be used as one.
+#[allow(unused)]
+const DICTIONARY: &[u8] =
+    br#"{"id": 0, "name": "", "tags": [], "created": "2020-01-01T00:00:00Z"}"#;
+
+#[allow(unused)]
+const MESSAGE: &[u8] =
+    br#"{"id": 42, "name": "example", "tags": ["a"], "created": "2020-06-01T12:00:00Z"}"#;
+
+/// `MESSAGE` compressed by zlib with `DICTIONARY` preset.
+#[allow(unused)]
+const COMPRESSED: &[u8] = &[
+    120, 249, 84, 98, 16, 220, 171, 134, 152, 97, 98, 132, 100, 72, 106, 69, 98, 110, 65, 78, 42,
+    178, 89, 74, 137, 74, 88, 141, 51, 3, 25, 103, 104, 4, 55, 14, 0, 91, 171, 20, 171,
+];
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn zlib_stream_dictionary_roundtrip() {
+    use utils::prelude::*;
+
+    let encoder = utils::zlib::stream::Encoder::with_quality_and_dictionary(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+        Level::Default,
+        DICTIONARY,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    let plain = stream_to_vec(utils::zlib::stream::Encoder::new(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+    ));
+    assert!(compressed.len() < plain.len());
+    // The header names the dictionary by its Adler-32
+    assert_eq!(compressed[2..6], [0x54, 0x62, 0x10, 0xdc]);
+
+    let decoder = utils::zlib::stream::Decoder::with_dictionary(
+        utils::InputStream::from(vec![compressed]).stream(),
+        DICTIONARY,
+    );
+    assert_eq!(stream_to_vec(decoder), MESSAGE);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_futures_decompress_with_dictionary() {
+    use utils::prelude::*;
+
+    let output = async_write_to_vec(
+        &[COMPRESSED.to_vec()],
+        |output| {
+            Box::pin(utils::zlib::futures::write::Decoder::with_dictionary(
+                output, DICTIONARY,
+            ))
+        },
+        4,
+    );
+    assert_eq!(output, MESSAGE);
+
+    // Streams without a dictionary are still accepted
+    let decoder = utils::zlib::futures::bufread::Decoder::with_dictionary(
+        utils::InputStream::from(vec![utils::zlib::sync::compress(MESSAGE)]).reader(),
+        DICTIONARY,
+    );
+    assert_eq!(async_read_to_vec(decoder), MESSAGE);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_futures_decompress_multiple_members_with_dictionary() {
+    use utils::prelude::*;
+
+    let compressed = [COMPRESSED, COMPRESSED].concat();
+
+    let mut decoder = utils::zlib::futures::bufread::Decoder::with_dictionary(
+        utils::InputStream::from(
+            compressed
+                .chunks(3)
+                .map(|chunk| chunk.to_vec())
+                .collect::<Vec<_>>(),
+        )
+        .reader(),
+        DICTIONARY,
+    );
+    decoder.multiple_members(true);
+    assert_eq!(async_read_to_vec(decoder), [MESSAGE, MESSAGE].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn zlib_tokio_decompress_without_dictionary() {
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let mut decoder = utils::zlib::tokio::bufread::Decoder::new(COMPRESSED);
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+
+    let required = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<DictionaryRequired>())
+        .unwrap();
+    assert_eq!(required.id(), 0x5462_10dc);
+    assert_eq!(required.given_id(), None);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio")]
+fn zlib_tokio_decompress_with_wrong_dictionary() {
+    use tokio::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let mut decoder = utils::zlib::tokio::bufread::Decoder::with_dictionary(COMPRESSED, MESSAGE);
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+
+    let required = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<DictionaryRequired>())
+        .unwrap();
+    assert_eq!(required.id(), 0x5462_10dc);
+    assert_ne!(required.given_id(), None);
+    assert_ne!(required.given_id(), Some(0x5462_10dc));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_futures_compress_with_window_bits() {
+    use async_compression::FlateParams;
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let input = [MESSAGE; 4].concat();
+
+    let compressed = async_read_to_vec(
+        utils::zlib::futures::bufread::Encoder::with_quality_and_params(
+            utils::InputStream::from(vec![input.clone()]).reader(),
+            Level::Default,
+            FlateParams::default().window_bits(10),
+        ),
+    );
+    // The header records the window size
+    assert_eq!(compressed[0], 0x28);
+    assert_eq!(utils::zlib::sync::decompress(&compressed), input);
+
+    let mut decoder = utils::zlib::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.window_bits(10);
+    assert_eq!(async_read_to_vec(decoder), input);
+
+    let mut decoder = utils::zlib::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.window_bits(9);
+    let mut output = Vec::new();
+    let err = block_on(decoder.read_to_end(&mut output)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -2,3 +2,353 @@
 mod utils;
 
 test_cases!(zstd);
+
+#[allow(unused)]
+use async_compression::{ZstdDecoderDictionary, ZstdEncoderDictionary};
+
+/// A raw content dictionary, any bytes can be used as one.
+#[allow(unused)]
+const DICTIONARY: &[u8] =
+    br#"{"id": 0, "name": "", "tags": [], "created": "2020-01-01T00:00:00Z"}"#;
+
+#[allow(unused)]
+const MESSAGE: &[u8] =
+    br#"{"id": 42, "name": "example", "tags": ["a"], "created": "2020-06-01T12:00:00Z"}"#;
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn zstd_stream_dictionary_roundtrip() {
+    use utils::prelude::*;
+
+    let encoder = utils::zstd::stream::Encoder::with_quality_and_dictionary(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+        Level::Default,
+        DICTIONARY,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    let plain = stream_to_vec(utils::zstd::stream::Encoder::new(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).stream(),
+    ));
+    assert!(compressed.len() < plain.len());
+
+    let decoder = utils::zstd::stream::Decoder::with_dictionary(
+        utils::InputStream::from(vec![compressed.clone()]).stream(),
+        DICTIONARY,
+    )
+    .unwrap();
+    assert_eq!(stream_to_vec(decoder), MESSAGE);
+
+    let output = read_to_vec(
+        libzstd::stream::read::Decoder::with_dictionary(&compressed[..], DICTIONARY).unwrap(),
+    );
+    assert_eq!(output, MESSAGE);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_futures_prepared_dictionary_roundtrip() {
+    use utils::prelude::*;
+
+    let encoder_dictionary = ZstdEncoderDictionary::new(DICTIONARY, Level::Best);
+    let decoder_dictionary = ZstdDecoderDictionary::new(DICTIONARY);
+
+    for _ in 0..2 {
+        let encoder = utils::zstd::futures::bufread::Encoder::with_prepared_dictionary(
+            utils::InputStream::from(vec![MESSAGE.to_vec()]).reader(),
+            &encoder_dictionary,
+        )
+        .unwrap();
+        let compressed = async_read_to_vec(encoder);
+
+        let output = async_write_to_vec(
+            &[compressed],
+            |output| {
+                Box::pin(
+                    utils::zstd::futures::write::Decoder::with_prepared_dictionary(
+                        output,
+                        &decoder_dictionary,
+                    )
+                    .unwrap(),
+                )
+            },
+            16,
+        );
+        assert_eq!(output, MESSAGE);
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_futures_decompress_without_dictionary() {
+    use utils::prelude::*;
+
+    let encoder = utils::zstd::futures::bufread::Encoder::with_quality_and_dictionary(
+        utils::InputStream::from(vec![MESSAGE.to_vec()]).reader(),
+        Level::Default,
+        DICTIONARY,
+    )
+    .unwrap();
+    let compressed = async_read_to_vec(encoder);
+
+    let decoder = utils::zstd::futures::bufread::Decoder::new(
+        utils::InputStream::from(vec![compressed]).reader(),
+    );
+    pin_mut!(decoder);
+    let mut output = Vec::new();
+    assert!(block_on(futures::io::AsyncReadExt::read_to_end(
+        &mut decoder,
+        &mut output
+    ))
+    .is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_futures_bufread_stop_at_trailing_data() {
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let compressed = [
+        utils::zstd::sync::compress(&[1, 2, 3, 4, 5, 6]),
+        // A skippable frame holding 4 bytes
+        vec![0x5e, 0x2a, 0x4d, 0x18, 4, 0, 0, 0, 9, 9, 9, 9],
+        utils::zstd::sync::compress(&[6, 5, 4, 3, 2, 1]),
+        b"trailing".to_vec(),
+    ]
+    .concat();
+
+    let mut decoder = utils::zstd::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.multiple_members(true);
+    decoder.stop_at_trailing_data(true);
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6, 6, 5, 4, 3, 2, 1][..]);
+    assert_eq!(decoder.into_inner(), &b"trailing"[..]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn zstd_stream_params() {
+    use async_compression::{ZstdParams, ZstdStrategy};
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![b"hello world, hello world".to_vec()]);
+
+    let params = ZstdParams::default()
+        .window_log(20)
+        .strategy(ZstdStrategy::Btultra2)
+        .checksum(true)
+        .dictionary_id(false);
+    let encoder = utils::zstd::stream::Encoder::with_quality_and_params(
+        input.stream(),
+        Level::Default,
+        params,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    // The frame header descriptor follows the magic number, bit 2 is the checksum flag and the
+    // exponent of the window descriptor after it is `windowLog - 10`
+    assert_eq!(compressed[4] & 0b100, 0b100);
+    assert_eq!(compressed[5] >> 3, 20 - 10);
+    assert_eq!(utils::zstd::sync::decompress(&compressed), input.bytes());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "stream")]
+fn zstd_stream_invalid_params() {
+    use async_compression::ZstdParams;
+    use utils::prelude::*;
+
+    let params = ZstdParams::default().window_log(40);
+    assert!(utils::zstd::stream::Encoder::with_quality_and_params(
+        utils::InputStream::from(vec![]).stream(),
+        Level::Default,
+        params,
+    )
+    .is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_futures_window_log_max() {
+    use async_compression::ZstdParams;
+    use futures::io::AsyncReadExt;
+    use utils::prelude::*;
+
+    let input = utils::InputStream::from(vec![(0..10_000).map(|i| (i % 251) as u8).collect()]);
+
+    let params = ZstdParams::default()
+        .window_log(28)
+        .long_distance_matching(true);
+    let encoder = utils::zstd::futures::bufread::Encoder::with_quality_and_params(
+        input.reader(),
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+    let compressed = async_read_to_vec(encoder);
+
+    let mut decoder = utils::zstd::futures::bufread::Decoder::new(&compressed[..]);
+    let mut output = Vec::new();
+    assert!(block_on(decoder.read_to_end(&mut output)).is_err());
+
+    let mut decoder = utils::zstd::futures::bufread::Decoder::new(&compressed[..]);
+    decoder.window_log_max(28).unwrap();
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, input.bytes());
+}
+
+/// Enough input for several worker jobs of the minimum size.
+#[allow(unused)]
+fn multithreaded_input() -> Vec<u8> {
+    (0..3 << 20).map(|i: u32| (i * 7 ^ i >> 9) as u8).collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(all(feature = "zstdmt", feature = "stream"))]
+fn zstd_stream_multithreaded() {
+    use async_compression::ZstdParams;
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    let params = ZstdParams::default().nb_workers(2).job_size(512 << 10);
+    let encoder = utils::zstd::stream::Encoder::with_quality_and_params(
+        utils::InputStream::from(input.chunks(100_000).map(Vec::from).collect::<Vec<_>>()).stream(),
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+    let compressed = stream_to_vec(encoder);
+
+    assert_eq!(utils::zstd::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(all(feature = "zstdmt", feature = "futures-io"))]
+fn zstd_futures_bufread_multithreaded() {
+    use async_compression::ZstdParams;
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    let params = ZstdParams::default().nb_workers(4);
+    let encoder = utils::zstd::futures::bufread::Encoder::with_quality_and_params(
+        &input[..],
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+    let compressed = async_read_to_vec(encoder);
+
+    assert_eq!(utils::zstd::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(all(feature = "zstdmt", feature = "futures-io"))]
+fn zstd_futures_bufread_multithreaded_wakes_instead_of_blocking() {
+    use async_compression::ZstdParams;
+    use futures::{
+        io::AsyncRead,
+        task::{waker, ArcWake},
+    };
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+    use utils::prelude::*;
+
+    struct Wakes(AtomicUsize);
+
+    impl ArcWake for Wakes {
+        fn wake_by_ref(arc: &Arc<Self>) {
+            arc.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let input = multithreaded_input();
+    let wakes = Arc::new(Wakes(AtomicUsize::new(0)));
+    let waker = waker(wakes.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    // Reading has to wait on the workers, which must leave the task pending rather than block it
+    let mut encoder = utils::zstd::futures::bufread::Encoder::with_quality_and_params(
+        &input[..],
+        Level::Fastest,
+        ZstdParams::default().nb_workers(2),
+    )
+    .unwrap();
+    let mut compressed = Vec::new();
+    let mut buffer = [0; 1024];
+    let mut pending = 0;
+    loop {
+        match Pin::new(&mut encoder).poll_read(&mut cx, &mut buffer) {
+            Poll::Ready(Ok(0)) => break,
+            Poll::Ready(Ok(len)) => compressed.extend_from_slice(&buffer[..len]),
+            Poll::Ready(Err(err)) => panic!("{}", err),
+            Poll::Pending => {
+                pending += 1;
+                while wakes.0.load(Ordering::SeqCst) < pending {
+                    std::thread::yield_now();
+                }
+            }
+        }
+    }
+
+    assert!(pending > 0);
+    assert_eq!(utils::zstd::sync::decompress(&compressed), input);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(all(feature = "zstdmt", feature = "futures-io"))]
+fn zstd_futures_write_multithreaded_flush() {
+    use async_compression::ZstdParams;
+    use futures::io::AsyncWriteExt;
+    use std::io::Read;
+    use utils::prelude::*;
+
+    let input = multithreaded_input();
+
+    let params = ZstdParams::default().nb_workers(2).job_size(512 << 10);
+    let mut encoder = utils::zstd::futures::write::Encoder::with_quality_and_params(
+        Vec::new(),
+        Level::Fastest,
+        params,
+    )
+    .unwrap();
+
+    block_on(async {
+        encoder.write_all(&input).await.unwrap();
+        encoder.flush().await.unwrap();
+    });
+
+    // Flushing waits for the workers, so everything written so far can be decoded
+    let mut output = vec![0; input.len()];
+    libzstd::stream::read::Decoder::new(&encoder.get_ref()[..])
+        .unwrap()
+        .read_exact(&mut output)
+        .unwrap();
+    assert_eq!(output, input);
+
+    block_on(encoder.close()).unwrap();
+    assert_eq!(utils::zstd::sync::decompress(encoder.get_ref()), input);
+}